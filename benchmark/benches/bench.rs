@@ -1,103 +1,339 @@
-// criterion is benchmarkinf framwork for Rust 
+// criterion is benchmarkinf framwork for Rust
 // prevent the compiler from optimizing away computations in a benchmark.
-use criterion::{criterion_group, criterion_main, Criterion, black_box,BenchmarkGroup,Throughput};
-use benchmark::{sample_data, SampleData}; // import sample data
-use bincode;
-use bcs;
-use serde_json;
-use borsh;
-use rmp_serde;
-use prost::Message;
+use criterion::{criterion_group, criterion_main, Criterion, black_box, BenchmarkGroup, Throughput};
+use criterion::measurement::WallTime;
+use prost::Message as _;
+use protobuf::Message as _;
+use benchmark::{for_each_fixture, for_each_format, for_each_zero_copy_format, FixtureVisitor, Format, FormatVisitor};
+use benchmark::{Codec, Fixture, Payload, Record, SampleData, ZeroCopyFormat, ZeroCopyVisitor};
+use benchmark::{sample_data, sample_data_large};
+use benchmark::borrowed::{for_each_borrowed_format, BorrowedFormat, BorrowedVisitor, SampleDataRef};
+use benchmark::fixtures::{sample_records, signed_transaction, SignedTransaction};
+use benchmark::scaling::{available_threads, run_parallel, thread_counts};
+use benchmark::stream::{encode_batch, read_records, write_records, RecordReader};
+use benchmark::zero_copy::{Plain, ZeroCopy};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
 
-fn benchmark_serialization(c: &mut Criterion) {  // for serialization
-    let mut group: BenchmarkGroup<_> = c.benchmark_group("Serialization");
-    let data = sample_data(); //data 
+// one group per fixture, one bench per format in it. throughput is set before
+// each bench from that format's encoded length so MB/s compare across formats
+struct Serialize<'a, 'g, T> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    data: &'a T,
+}
 
+impl<T: Payload> FormatVisitor for Serialize<'_, '_, T> {
+    fn visit<F: Format>(&mut self) {
+        let data = self.data;
+        self.group.throughput(Throughput::Bytes(F::encode(data).len() as u64));
+        self.group.bench_function(format!("{} serialize", F::NAME), |b| { // register a benchmark
+            b.iter(|| F::encode(black_box(data))) // repeats the test multiple time
+        });
+    }
+}
 
-    group.throughput(Throughput::Bytes(std::mem::size_of_val(&data) as u64));
+impl<T: Record> ZeroCopyVisitor for Serialize<'_, '_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let data = self.data;
+        self.group.throughput(Throughput::Bytes(Z::encode(data).len() as u64));
+        self.group.bench_function(format!("{} serialize", Z::NAME), |b| {
+            b.iter(|| Z::encode(black_box(data)))
+        });
+    }
+}
 
-    group.bench_function("bincode serialize", |b| { // register a benchmark
-        b.iter(|| bincode::serialize(black_box(&data)).unwrap()) // repeats the test multiple time
-    });
+struct Deserialize<'a, 'g, T> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    data: &'a T,
+}
 
-    group.bench_function("bcs serialize", |b| {
-        b.iter(|| bcs::to_bytes(black_box(&data)).unwrap())
-    });
+impl<T: Payload> FormatVisitor for Deserialize<'_, '_, T> {
+    fn visit<F: Format>(&mut self) {
+        let bytes = F::encode(self.data);
+        self.group.throughput(Throughput::Bytes(bytes.len() as u64));
+        self.group.bench_function(format!("{} deserialize", F::NAME), |b| {
+            b.iter(|| F::decode::<T>(black_box(&bytes)).unwrap())
+        });
+    }
+}
 
-    group.bench_function("serde_json serialize", |b| {
-        b.iter(|| serde_json::to_vec(black_box(&data)).unwrap())
-    });
+// full materialisation into the owned type
+impl<T: Record> ZeroCopyVisitor for Deserialize<'_, '_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let bytes = Z::encode(self.data);
+        self.group.throughput(Throughput::Bytes(bytes.len() as u64));
+        self.group.bench_function(format!("{} deserialize", Z::NAME), |b| {
+            b.iter(|| Z::decode::<T>(black_box(&bytes)).unwrap())
+        });
+    }
+}
 
-    group.bench_function("borsh serialize", |b| {
-        b.iter(|| borsh::to_vec(black_box(&data)).unwrap())
-    });
+// read only the key field, what the indexer does. normal formats have to decode
+// the whole record first, zero copy ones read it from the bytes. no throughput
+// here, only the time per lookup matters
+struct Access<'a, 'g, T> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    data: &'a T,
+}
 
-    group.bench_function("rmp serialize", |b| {
-        b.iter(|| rmp_serde::to_vec(black_box(&data)).unwrap())
-    });
+impl<T: Record> FormatVisitor for Access<'_, '_, T> {
+    fn visit<F: Format>(&mut self) {
+        let bytes = F::encode(self.data);
+        self.group.bench_function(format!("{} access", F::NAME), |b| {
+            b.iter(|| F::decode::<T>(black_box(&bytes)).unwrap().key())
+        });
+    }
+}
 
-    group.bench_function("protobuf serialize", |b| {
-        b.iter(|| data.encode_to_vec()) // Protobuf serialization
-    });
+impl<T: Record> ZeroCopyVisitor for Access<'_, '_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let bytes = Z::encode(self.data);
+        self.group.bench_function(format!("{} access", Z::NAME), |b| {
+            b.iter(|| Z::access::<T>(black_box(&bytes)).unwrap())
+        });
+    }
+}
 
-    group.finish();
+const RECORDS: usize = 1_000_000;
+
+// a million records in one length delimited buffer
+struct Batch<'a, 'g> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    records: &'a [SampleData],
 }
 
+impl Batch<'_, '_> {
+    fn run<C: Codec>(&mut self) {
+        let records = self.records;
+        let bytes = encode_batch::<C, _>(records);
+        self.group.throughput(Throughput::Bytes(bytes.len() as u64));
+        self.group.bench_function(format!("{} encode", C::NAME), |b| b.iter(|| encode_batch::<C, _>(black_box(records))));
+        self.group.bench_function(format!("{} decode", C::NAME), |b| {
+            b.iter(|| read_records::<C, SampleData, _>(black_box(&bytes[..])).unwrap())
+        });
+    }
+}
 
-fn benchmark_deserialization(c: &mut Criterion) {  // for deserialization
-    let mut group: BenchmarkGroup<_> = c.benchmark_group("Deserialization");
-    let data = sample_data();
-
-    let bincode_data = bincode::serialize(&data).unwrap();
-    let bcs_data = bcs::to_bytes(&data).unwrap();
-    let json_data = serde_json::to_vec(&data).unwrap();
-    let borsh_data = borsh::to_vec(&data).unwrap();
-    let rmp_data = rmp_serde::to_vec(&data).unwrap();
-    let protobuf_data = data.encode_to_vec();
-
-    group.bench_function("bincode deserialize", |b| {
-        b.iter(|| bincode::deserialize::<SampleData>(black_box(&bincode_data)).unwrap())
-    });
+impl FormatVisitor for Batch<'_, '_> {
+    fn visit<F: Format>(&mut self) {
+        self.run::<Plain<F>>();
+    }
+}
 
-    group.bench_function("bcs deserialize", |b| {
-        b.iter(|| bcs::from_bytes::<SampleData>(black_box(&bcs_data)).unwrap())
-    });
+impl ZeroCopyVisitor for Batch<'_, '_> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.run::<ZeroCopy<Z>>();
+    }
+}
 
-    group.bench_function("serde_json deserialize", |b| {
-        b.iter(|| serde_json::from_slice::<SampleData>(black_box(&json_data)).unwrap())
-    });
+// the same records streamed through a file. incremental decodes one record at a
+// time and only keeps the key, read collects them all
+struct Stream<'a, 'g> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    records: &'a [SampleData],
+    path: PathBuf,
+}
 
-    group.bench_function("borsh deserialize", |b| {
-        b.iter(|| borsh::from_slice::<SampleData>(black_box(&borsh_data)).unwrap())
-    });
+impl Stream<'_, '_> {
+    fn run<C: Codec>(&mut self) {
+        let (records, path) = (self.records, &self.path);
+        let write = || {
+            let mut writer = BufWriter::new(File::create(path).unwrap());
+            write_records::<C, _, _>(&mut writer, black_box(records)).unwrap();
+            writer.flush().unwrap();
+        };
+        write();
+        self.group.throughput(Throughput::Bytes(fs::metadata(path).unwrap().len()));
+        self.group.bench_function(format!("{} write", C::NAME), |b| b.iter(write));
+        self.group.bench_function(format!("{} read", C::NAME), |b| {
+            b.iter(|| read_records::<C, SampleData, _>(BufReader::new(File::open(path).unwrap())).unwrap())
+        });
+        self.group.bench_function(format!("{} incremental", C::NAME), |b| {
+            b.iter(|| {
+                RecordReader::<C, SampleData, _>::new(BufReader::new(File::open(path).unwrap()))
+                    .map(|record| record.unwrap().key())
+                    .sum::<u64>()
+            })
+        });
+    }
+}
+
+impl FormatVisitor for Stream<'_, '_> {
+    fn visit<F: Format>(&mut self) {
+        self.run::<Plain<F>>();
+    }
+}
+
+impl ZeroCopyVisitor for Stream<'_, '_> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.run::<ZeroCopy<Z>>();
+    }
+}
 
-    group.bench_function("rmp deserialize", |b| {
-        b.iter(|| rmp_serde::from_slice::<SampleData>(black_box(&rmp_data)).unwrap())
+// the same encode and decode on n threads at once through the Codec adapters,
+// each thread on its own copy of the transaction or its bytes.
+// throughput is n calls per iteration, so elements/s is the total over all
+// threads and flat elements/s means no scaling. efficiency is in scaling_report
+struct Scaling<'a, 'g> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    data: &'a SignedTransaction,
+    threads: usize,
+}
+
+impl Scaling<'_, '_> {
+    fn run<C: Codec>(&mut self) {
+        let (data, threads) = (self.data, self.threads);
+        let bytes = C::encode(data);
+        self.group.throughput(Throughput::Elements(threads as u64));
+        self.group.bench_function(format!("{} serialize", C::NAME), |b| {
+            b.iter_custom(|iters| run_parallel(threads, iters, || data.clone(), |data| C::encode(black_box(data))))
+        });
+        self.group.bench_function(format!("{} deserialize", C::NAME), |b| {
+            b.iter_custom(|iters| run_parallel(threads, iters, || bytes.clone(), |bytes| C::decode::<SignedTransaction>(black_box(bytes)).unwrap()))
+        });
+    }
+}
+
+impl FormatVisitor for Scaling<'_, '_> {
+    fn visit<F: Format>(&mut self) {
+        self.run::<Plain<F>>();
+    }
+}
+
+impl ZeroCopyVisitor for Scaling<'_, '_> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.run::<ZeroCopy<Z>>();
+    }
+}
+
+// decode into the owned SampleData against SampleDataRef, which points into the
+// bytes where the format allows it
+struct Borrowed<'a, 'g> {
+    group: &'a mut BenchmarkGroup<'g, WallTime>,
+    data: &'a SampleData,
+}
+
+impl BorrowedVisitor for Borrowed<'_, '_> {
+    fn visit<F: BorrowedFormat>(&mut self) {
+        let bytes = F::encode(self.data);
+        self.group.throughput(Throughput::Bytes(bytes.len() as u64));
+        self.group.bench_function(format!("{} owned", F::NAME), |b| {
+            b.iter(|| F::decode::<SampleData>(black_box(&bytes)).unwrap())
+        });
+        self.group.bench_function(format!("{} borrowed", F::NAME), |b| {
+            b.iter(|| F::decode_borrowed::<SampleDataRef>(black_box(&bytes)).unwrap())
+        });
+    }
+}
+
+// the two protobuf implementations on the types generated from proto/payloads.proto
+fn protobuf<T: Fixture>(group: &mut BenchmarkGroup<WallTime>, data: &T) {
+    let prost = data.to_prost();
+    let bytes = prost.encode_to_vec();
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("prost-build serialize", |b| b.iter(|| black_box(&prost).encode_to_vec()));
+    group.bench_function("prost-build deserialize", |b| {
+        b.iter(|| <T::Prost as prost::Message>::decode(black_box(&bytes[..])).unwrap())
     });
 
-    group.bench_function("protobuf deserialize", |b| {
-        b.iter(|| SampleData::decode(black_box(&*protobuf_data)).unwrap())
+    let rust_protobuf = data.to_rust_protobuf();
+    let bytes = rust_protobuf.write_to_bytes().unwrap();
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("rust-protobuf serialize", |b| b.iter(|| black_box(&rust_protobuf).write_to_bytes().unwrap()));
+    group.bench_function("rust-protobuf deserialize", |b| {
+        b.iter(|| T::RustProtobuf::parse_from_bytes(black_box(&bytes)).unwrap())
     });
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Serialization,
+    Deserialization,
+    Access,
+    Protobuf,
+}
+
+struct Fixtures<'a> {
+    c: &'a mut Criterion,
+    kind: Kind,
+}
+
+impl FixtureVisitor for Fixtures<'_> {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        let mut group = self.c.benchmark_group(format!("{:?}/{}", self.kind, name));
+        match self.kind {
+            Kind::Serialization => {
+                for_each_format(&mut Serialize { group: &mut group, data });
+                for_each_zero_copy_format(&mut Serialize { group: &mut group, data });
+            }
+            Kind::Deserialization => {
+                for_each_format(&mut Deserialize { group: &mut group, data });
+                for_each_zero_copy_format(&mut Deserialize { group: &mut group, data });
+            }
+            Kind::Access => {
+                for_each_format(&mut Access { group: &mut group, data });
+                for_each_zero_copy_format(&mut Access { group: &mut group, data });
+            }
+            Kind::Protobuf => protobuf(&mut group, data),
+        }
+        group.finish();
+    }
+}
+
+fn benchmark_serialization(c: &mut Criterion) {  // for serialization
+    for_each_fixture(&mut Fixtures { c, kind: Kind::Serialization });
+}
+
+fn benchmark_deserialization(c: &mut Criterion) {  // for deserialization
+    for_each_fixture(&mut Fixtures { c, kind: Kind::Deserialization });
+}
+
+fn benchmark_access(c: &mut Criterion) { // read one field of a stored record
+    for_each_fixture(&mut Fixtures { c, kind: Kind::Access });
+}
+
+fn benchmark_protobuf(c: &mut Criterion) { // prost-build against rust-protobuf
+    for_each_fixture(&mut Fixtures { c, kind: Kind::Protobuf });
+}
+
+fn benchmark_batch(c: &mut Criterion) { // encode and decode a million records at once
+    let records = sample_records(RECORDS);
+    let mut group = c.benchmark_group(format!("Batch/{}_records", RECORDS));
+    group.sample_size(10);
+    for_each_format(&mut Batch { group: &mut group, records: &records });
+    for_each_zero_copy_format(&mut Batch { group: &mut group, records: &records });
     group.finish();
 }
 
-fn benchmark_size(c: &mut Criterion) { // check size of data
-    let data = sample_data();
+fn benchmark_stream(c: &mut Criterion) { // same records through a Write and Read
+    let records = sample_records(RECORDS);
+    let path = std::env::temp_dir().join("benchmark_stream.bin");
+    let mut group = c.benchmark_group(format!("Stream/{}_records", RECORDS));
+    group.sample_size(10);
+    for_each_format(&mut Stream { group: &mut group, records: &records, path: path.clone() });
+    for_each_zero_copy_format(&mut Stream { group: &mut group, records: &records, path: path.clone() });
+    group.finish();
+    let _ = fs::remove_file(path);
+}
 
-    let bincode_size = bincode::serialize(&data).unwrap().len();
-    let bcs_size = bcs::to_bytes(&data).unwrap().len();
-    let json_size = serde_json::to_vec(&data).unwrap().len();
-    let borsh_size = borsh::to_vec(&data).unwrap().len();
-    let rmp_size = rmp_serde::to_vec(&data).unwrap().len();
-    let protobuf_size = data.encode_to_vec().len();
+fn benchmark_scaling(c: &mut Criterion) { // an erc20 transfer, about the size of a backend response
+    let data = signed_transaction(68);
+    for threads in thread_counts(available_threads()) {
+        let mut group = c.benchmark_group(format!("Scaling/{}_threads", threads));
+        for_each_format(&mut Scaling { group: &mut group, data: &data, threads });
+        for_each_zero_copy_format(&mut Scaling { group: &mut group, data: &data, threads });
+        group.finish();
+    }
+}
 
-    c.bench_function("size bincode", |b| b.iter(|| black_box(bincode_size)));
-    c.bench_function("size bcs", |b| b.iter(|| black_box(bcs_size)));
-    c.bench_function("size json", |b| b.iter(|| black_box(json_size)));
-    c.bench_function("size borsh", |b| b.iter(|| black_box(borsh_size)));
-    c.bench_function("size rmp", |b| b.iter(|| black_box(rmp_size)));
-    c.bench_function("size protobuf", |b| b.iter(|| black_box(protobuf_size)));
+fn benchmark_borrowed(c: &mut Criterion) { // only SampleData has a borrowed version
+    for (name, data) in [("sample/small", sample_data()), ("sample/large", sample_data_large())] {
+        let mut group = c.benchmark_group(format!("Borrowed/{}", name));
+        for_each_borrowed_format(&mut Borrowed { group: &mut group, data: &data });
+        group.finish();
+    }
 }
 
-criterion_group!(benches, benchmark_serialization, benchmark_deserialization, benchmark_size); // group all bechmarks
+criterion_group!(benches, benchmark_serialization, benchmark_deserialization, benchmark_access, benchmark_protobuf, benchmark_batch, benchmark_stream, benchmark_scaling, benchmark_borrowed); // group all bechmarks, sizes come from the size_report binary
 criterion_main!(benches);
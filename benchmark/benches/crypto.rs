@@ -0,0 +1,108 @@
+// the crypto primitives the other crates spend their time in: the merkle_tree
+// hashers, keccak, signing a transaction like web3_ethereum_wallet does and
+// rlp encoding the signed transaction
+use criterion::{criterion_group, criterion_main, Criterion, black_box, BenchmarkGroup, Throughput};
+use criterion::measurement::WallTime;
+use ethers::core::types::transaction::eip2718::TypedTransaction;
+use ethers::core::types::{Address, Eip1559TransactionRequest, TransactionRequest, U256};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::utils::{keccak256, parse_ether, rlp::Rlp};
+use merkle_tree::{Blake2bHasher, Hasher, Sha256Hasher, Sha512Hasher};
+
+const CHAIN_ID: u64 = 11155111; // sepolia, what the wallet sends to
+
+fn wallet() -> LocalWallet {
+    // fixed key so every run signs the same bytes
+    "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse::<LocalWallet>().unwrap().with_chain_id(CHAIN_ID)
+}
+
+// the same transaction web3_ethereum_wallet builds for a transfer
+fn legacy(from: Address) -> TypedTransaction {
+    let mut tx: TypedTransaction = TransactionRequest {
+        from: Some(from),
+        to: Some(Address::repeat_byte(0x11).into()),
+        value: Some(parse_ether("0.01").unwrap()),
+        gas: Some(U256::from(21000)),
+        gas_price: Some(U256::from(20_000_000_000u64)),
+        nonce: Some(U256::from(7)),
+        data: None,
+        chain_id: None,
+    }
+    .into();
+    tx.set_chain_id(CHAIN_ID);
+    tx
+}
+
+// a contract call with calldata, as an eip-1559 transaction
+fn eip1559(from: Address) -> TypedTransaction {
+    let mut data = vec![0x60, 0xfe, 0x47, 0xb1]; // set(uint256)
+    data.extend_from_slice(&[0u8; 31]);
+    data.push(42);
+    Eip1559TransactionRequest::new()
+        .from(from)
+        .to(Address::repeat_byte(0x22))
+        .value(0)
+        .gas(60000)
+        .max_fee_per_gas(30_000_000_000u64)
+        .max_priority_fee_per_gas(1_500_000_000u64)
+        .nonce(7)
+        .data(data)
+        .chain_id(CHAIN_ID)
+        .into()
+}
+
+fn bench_hasher<H: Hasher>(group: &mut BenchmarkGroup<WallTime>, input: &[u8]) {
+    group.bench_function(H::NAME, |b| b.iter(|| H::hash(black_box(input))));
+}
+
+// merkle_tree hashers return hex strings, so they include the hex encoding
+fn benchmark_hash(c: &mut Criterion) {
+    for size in [32, 1024, 64 * 1024] {
+        let input: Vec<u8> = (0..size).map(|i| i as u8).collect();
+        let mut group = c.benchmark_group(format!("Hash/{}B", size));
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function("keccak256", |b| b.iter(|| keccak256(black_box(&input))));
+        bench_hasher::<Sha256Hasher>(&mut group, &input);
+        bench_hasher::<Sha512Hasher>(&mut group, &input);
+        bench_hasher::<Blake2bHasher>(&mut group, &input);
+        group.finish();
+    }
+}
+
+fn benchmark_secp256k1(c: &mut Criterion) {
+    let wallet = wallet();
+    let tx = legacy(wallet.address());
+    let sighash = tx.sighash();
+    let signature = wallet.sign_transaction_sync(&tx).unwrap();
+
+    let mut group = c.benchmark_group("Secp256k1");
+    group.bench_function("sign_transaction", |b| {
+        b.iter(|| wallet.sign_transaction_sync(black_box(&tx)).unwrap())
+    });
+    // signing without the rlp and keccak of the transaction
+    group.bench_function("sign_hash", |b| b.iter(|| wallet.sign_hash(black_box(sighash)).unwrap()));
+    group.bench_function("recover", |b| b.iter(|| black_box(&signature).recover(sighash).unwrap()));
+    group.bench_function("parse key", |b| {
+        b.iter(|| black_box("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").parse::<LocalWallet>().unwrap())
+    });
+    group.finish();
+}
+
+fn benchmark_rlp(c: &mut Criterion) {
+    let wallet = wallet();
+    let mut group = c.benchmark_group("Rlp");
+    for (name, tx) in [("legacy", legacy(wallet.address())), ("eip1559", eip1559(wallet.address()))] {
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        let signed = tx.rlp_signed(&signature);
+        group.bench_function(format!("{} rlp", name), |b| b.iter(|| black_box(&tx).rlp()));
+        group.bench_function(format!("{} sighash", name), |b| b.iter(|| black_box(&tx).sighash()));
+        group.bench_function(format!("{} rlp_signed", name), |b| b.iter(|| black_box(&tx).rlp_signed(&signature)));
+        group.bench_function(format!("{} decode_signed", name), |b| {
+            b.iter(|| TypedTransaction::decode_signed(&Rlp::new(black_box(&signed))).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_hash, benchmark_secp256k1, benchmark_rlp);
+criterion_main!(benches);
@@ -0,0 +1,82 @@
+// merkle_tree build, proof and verify for every hasher at 1k, 100k and 1M leaves.
+// criterion gives the time, the counting allocator gives the memory, printed as
+// a table before the time benches of each size
+use criterion::{criterion_group, criterion_main, Criterion, black_box, BatchSize, BenchmarkGroup, Throughput};
+use criterion::measurement::WallTime;
+use benchmark::allocations::{measure, AllocStats, CountingAllocator};
+use merkle_tree::{Blake2bHasher, Hasher, MerkleTree, Sha256Hasher, Sha512Hasher};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+fn leaves(n: usize) -> Vec<Vec<u8>> {
+    (0..n).map(|i| format!("leaf{}", i).into_bytes()).collect()
+}
+
+fn memory_row(operation: &str, hasher: &str, stats: AllocStats) {
+    println!("| {} | {} | {} | {} | {} |", hasher, operation, stats.allocations, stats.bytes, stats.peak);
+}
+
+// leaves are already allocated when measuring, so only the tree's own memory counts
+fn memory<H: Hasher>(leaves: &[Vec<u8>]) {
+    let owned = leaves.to_vec();
+    let (tree, build) = measure(move || MerkleTree::<H>::new(owned));
+    let index = leaves.len() / 2;
+    let (proof, get_proof) = measure(|| tree.get_proof(index));
+    let (leaf, root) = (leaves[index].clone(), tree.get_root());
+    let (valid, verify) = measure(move || MerkleTree::<H>::verify_proof(leaf, proof, root));
+    assert!(valid);
+    memory_row("build", H::NAME, build);
+    memory_row("get_proof", H::NAME, get_proof);
+    memory_row("verify_proof", H::NAME, verify);
+}
+
+fn bench_hasher<H: Hasher>(group: &mut BenchmarkGroup<WallTime>, leaves: &[Vec<u8>]) {
+    let tree = MerkleTree::<H>::new(leaves.to_vec());
+    let index = leaves.len() / 2;
+    let proof = tree.get_proof(index);
+    let root = tree.get_root();
+
+    // new() takes the leaves by value, the copy is made outside the timing. build
+    // handles every leaf, a proof and its check only one
+    group.throughput(Throughput::Elements(leaves.len() as u64));
+    group.bench_function(format!("{} build", H::NAME), |b| {
+        b.iter_batched(|| leaves.to_vec(), |leaves| MerkleTree::<H>::new(leaves), BatchSize::LargeInput)
+    });
+    group.throughput(Throughput::Elements(1));
+    group.bench_function(format!("{} get_proof", H::NAME), |b| b.iter(|| tree.get_proof(black_box(index))));
+    group.bench_function(format!("{} verify_proof", H::NAME), |b| {
+        b.iter_batched(
+            || (leaves[index].clone(), proof.clone(), root.clone()),
+            |(leaf, proof, root)| MerkleTree::<H>::verify_proof(leaf, proof, root),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn benchmark_merkle(c: &mut Criterion) {
+    for n in SIZES {
+        let leaves = leaves(n);
+
+        println!("\nMerkle memory, {} leaves\n", n);
+        println!("| hasher | operation | allocations | bytes | peak |\n|---|---|---:|---:|---:|");
+        memory::<Sha256Hasher>(&leaves);
+        memory::<Sha512Hasher>(&leaves);
+        memory::<Blake2bHasher>(&leaves);
+        println!();
+
+        let mut group = c.benchmark_group(format!("Merkle/{}_leaves", n));
+        if n > 1_000 {
+            group.sample_size(10); // a 1M leaf build takes about a second
+        }
+        bench_hasher::<Sha256Hasher>(&mut group, &leaves);
+        bench_hasher::<Sha512Hasher>(&mut group, &leaves);
+        bench_hasher::<Blake2bHasher>(&mut group, &leaves);
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_merkle);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+// compiles proto/payloads.proto with prost-build and with rust-protobuf so the
+// two protobuf implementations run on the same schema. protoc comes from
+// protoc-bin-vendored, nothing has to be installed. schema/payloads.fbs goes
+// through planus. cap'n proto needs the capnp tool, so its code is checked in
+// as src/payloads_capnp.rs, see schema/payloads.capnp
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=proto/payloads.proto");
+    println!("cargo:rerun-if-changed=schema/payloads.fbs");
+    let protoc = protoc_bin_vendored::protoc_bin_path().unwrap();
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let prost_out = out.join("prost");
+    fs::create_dir_all(&prost_out).unwrap();
+    prost_build::Config::new()
+        .protoc_executable(&protoc)
+        .out_dir(&prost_out)
+        .compile_protos(&["proto/payloads.proto"], &["proto"])
+        .unwrap();
+
+    protobuf_codegen::Codegen::new()
+        .protoc()
+        .protoc_path(&protoc)
+        .includes(["proto"])
+        .input("proto/payloads.proto")
+        .cargo_out_dir("rust_protobuf")
+        .run_from_script();
+
+    let declarations = planus_translation::translate_files(&[Path::new("schema/payloads.fbs")]).expect("schema/payloads.fbs");
+    fs::write(out.join("payloads_fbs.rs"), planus_codegen::generate_rust(&declarations, false).unwrap()).unwrap();
+}
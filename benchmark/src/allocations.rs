@@ -0,0 +1,151 @@
+// allocation profile of every format: allocations, bytes allocated and peak heap
+// for one encode, decode or field access. counts only move when CountingAllocator
+// is the global allocator, the alloc_report binary installs it
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use crate::{for_each_fixture, for_each_format, for_each_zero_copy_format, Fixture, FixtureVisitor, Format, FormatVisitor};
+use crate::{Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// system allocator that counts. a realloc counts as one allocation of the new size
+pub struct CountingAllocator;
+
+fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    BYTES.fetch_add(size, Relaxed);
+    let current = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(current, Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        allocated(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        allocated(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+        allocated(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    // highest heap use above what was live before the call
+    pub peak: usize,
+}
+
+// what f allocates, its result is still counted because it is dropped after
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let base = CURRENT.load(Relaxed);
+    PEAK.store(base, Relaxed);
+    let result = f();
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+        bytes: BYTES.load(Relaxed) - bytes,
+        peak: PEAK.load(Relaxed) - base,
+    };
+    (result, stats)
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct AllocRow {
+    pub fixture: String,
+    pub format: String,
+    pub operation: String,
+    #[serde(flatten)]
+    pub stats: AllocStats,
+}
+
+struct Rows<'a, T> {
+    rows: &'a mut Vec<AllocRow>,
+    fixture: &'a str,
+    data: &'a T,
+}
+
+impl<T> Rows<'_, T> {
+    fn push(&mut self, format: &str, operation: &str, stats: AllocStats) {
+        self.rows.push(AllocRow { fixture: self.fixture.to_string(), format: format.to_string(), operation: operation.to_string(), stats });
+    }
+}
+
+impl<T: Record> FormatVisitor for Rows<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        let (bytes, encode) = measure(|| F::encode(self.data));
+        let (_, decode) = measure(|| F::decode::<T>(&bytes).unwrap());
+        let (_, access) = measure(|| F::decode::<T>(&bytes).unwrap().key());
+        self.push(F::NAME, "encode", encode);
+        self.push(F::NAME, "decode", decode);
+        self.push(F::NAME, "access", access);
+    }
+}
+
+impl<T: Record> ZeroCopyVisitor for Rows<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let (bytes, encode) = measure(|| Z::encode(self.data));
+        let (_, decode) = measure(|| Z::decode::<T>(&bytes).unwrap());
+        let (_, access) = measure(|| Z::access::<T>(&bytes).unwrap());
+        self.push(Z::NAME, "encode", encode);
+        self.push(Z::NAME, "decode", decode);
+        self.push(Z::NAME, "access", access);
+    }
+}
+
+struct Fixtures(Vec<AllocRow>);
+
+impl FixtureVisitor for Fixtures {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        for_each_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+        for_each_zero_copy_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+    }
+}
+
+pub fn alloc_rows() -> Vec<AllocRow> {
+    let mut fixtures = Fixtures(Vec::new());
+    for_each_fixture(&mut fixtures);
+    fixtures.0
+}
+
+// one table per fixture like the size report
+pub fn to_markdown(rows: &[AllocRow]) -> String {
+    let mut out = String::from("# Allocations\n\n");
+    out.push_str("Per single call: number of allocations, bytes allocated and peak heap above the start. ");
+    out.push_str("access reads the key, regular formats have to decode the whole record for it.\n");
+    let mut fixture = "";
+    for row in rows {
+        if row.fixture != fixture {
+            fixture = &row.fixture;
+            out.push_str(&format!("\n## {}\n\n", fixture));
+            out.push_str("| format | operation | allocations | bytes | peak |\n");
+            out.push_str("|---|---|---:|---:|---:|\n");
+        }
+        let s = row.stats;
+        out.push_str(&format!("| {} | {} | {} | {} | {} |\n", row.format, row.operation, s.allocations, s.bytes, s.peak));
+    }
+    out
+}
+
+pub fn to_json(rows: &[AllocRow]) -> String {
+    serde_json::to_string_pretty(rows).unwrap()
+}
@@ -0,0 +1,249 @@
+// stored benchmark results and regression checks. a run times encode, decode and
+// access of every format on every fixture and is saved as json with the git
+// revision and machine, a later run is compared to it row by row
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{for_each_fixture, for_each_format, for_each_zero_copy_format, Fixture, FixtureVisitor, Format, FormatVisitor};
+use crate::{Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+const SAMPLES: usize = 11;
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Measurement {
+    pub fixture: String,
+    pub format: String,
+    pub operation: String,
+    // median time of one call
+    pub ns: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Machine {
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+    pub cpu: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Run {
+    // short hash, with -dirty when the tree had changes
+    pub revision: String,
+    pub machine: Machine,
+    pub timestamp: u64,
+    pub results: Vec<Measurement>,
+}
+
+// runs f in samples of enough calls to take SAMPLE_TIME, returns the median ns per call
+pub fn time<R>(mut f: impl FnMut() -> R) -> f64 {
+    let mut iterations = 1u64;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(f());
+        }
+        if start.elapsed() >= SAMPLE_TIME {
+            break;
+        }
+        iterations *= 2;
+    }
+    let mut samples: Vec<f64> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .collect();
+    samples.sort_by(|a, b| a.total_cmp(b));
+    samples[SAMPLES / 2]
+}
+
+struct Rows<'a, T> {
+    rows: &'a mut Vec<Measurement>,
+    fixture: &'a str,
+    data: &'a T,
+}
+
+impl<T> Rows<'_, T> {
+    fn push(&mut self, format: &str, operation: &str, ns: f64) {
+        self.rows.push(Measurement { fixture: self.fixture.to_string(), format: format.to_string(), operation: operation.to_string(), ns });
+    }
+}
+
+impl<T: Record> FormatVisitor for Rows<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        let data = self.data;
+        let bytes = F::encode(data);
+        self.push(F::NAME, "encode", time(|| F::encode(data)));
+        self.push(F::NAME, "decode", time(|| F::decode::<T>(&bytes).unwrap()));
+        self.push(F::NAME, "access", time(|| F::decode::<T>(&bytes).unwrap().key()));
+    }
+}
+
+impl<T: Record> ZeroCopyVisitor for Rows<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let data = self.data;
+        let bytes = Z::encode(data);
+        self.push(Z::NAME, "encode", time(|| Z::encode(data)));
+        self.push(Z::NAME, "decode", time(|| Z::decode::<T>(&bytes).unwrap()));
+        self.push(Z::NAME, "access", time(|| Z::access::<T>(&bytes).unwrap()));
+    }
+}
+
+struct Fixtures(Vec<Measurement>);
+
+impl FixtureVisitor for Fixtures {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        for_each_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+        for_each_zero_copy_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+    }
+}
+
+pub fn run_suite() -> Vec<Measurement> {
+    let mut fixtures = Fixtures(Vec::new());
+    for_each_fixture(&mut fixtures);
+    fixtures.0
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn git_revision() -> String {
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(hash) if git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => format!("{}-dirty", hash),
+        Some(hash) => hash,
+        None => "unknown".to_string(),
+    }
+}
+
+pub fn machine() -> Machine {
+    // cpu model only on linux, good enough to see two runs came from different boxes
+    let cpu = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| info.lines().find(|l| l.starts_with("model name")).and_then(|l| l.split(':').nth(1)).map(|m| m.trim().to_string()))
+        .unwrap_or("unknown".to_string());
+    Machine {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpus: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        cpu,
+    }
+}
+
+impl Run {
+    pub fn new(results: Vec<Measurement>) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        Run { revision: git_revision(), machine: machine(), timestamp, results }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Comparison {
+    pub fixture: String,
+    pub format: String,
+    pub operation: String,
+    pub baseline_ns: f64,
+    pub current_ns: f64,
+    // relative change, 0.25 is 25% slower
+    pub change: f64,
+    pub regressed: bool,
+}
+
+fn same_row(a: &Measurement, b: &Measurement) -> bool {
+    a.fixture == b.fixture && a.format == b.format && a.operation == b.operation
+}
+
+// rows of run that other has no row for. unmatched(baseline, current) went
+// missing, a format or fixture was dropped or renamed, and compare can not see
+// it. unmatched(current, baseline) is new and has nothing to compare to yet
+pub fn unmatched<'a>(run: &'a Run, other: &Run) -> Vec<&'a Measurement> {
+    run.results.iter().filter(|r| !other.results.iter().any(|o| same_row(r, o))).collect()
+}
+
+// rows found in both runs, threshold is relative (0.1 is 10% slower)
+pub fn compare(baseline: &Run, current: &Run, threshold: f64) -> Vec<Comparison> {
+    current
+        .results
+        .iter()
+        .filter_map(|c| {
+            let b = baseline.results.iter().find(|b| same_row(b, c))?;
+            let change = c.ns / b.ns - 1.0;
+            Some(Comparison {
+                fixture: c.fixture.clone(),
+                format: c.format.clone(),
+                operation: c.operation.clone(),
+                baseline_ns: b.ns,
+                current_ns: c.ns,
+                change,
+                regressed: change > threshold,
+            })
+        })
+        .collect()
+}
+
+// regressions first so they are what a reviewer sees
+pub fn to_markdown(baseline: &Run, current: &Run, comparisons: &[Comparison], threshold: f64) -> String {
+    let mut out = String::from("# Benchmark comparison\n\n");
+    out.push_str(&format!("baseline {} on {} ({} cpus), current {} on {} ({} cpus). ", baseline.revision, baseline.machine.cpu, baseline.machine.cpus, current.revision, current.machine.cpu, current.machine.cpus));
+    out.push_str(&format!("Regression threshold {:.0}%.\n", threshold * 100.0));
+    if baseline.machine != current.machine {
+        out.push_str("\nThe runs are from different machines, times are not comparable.\n");
+    }
+
+    let regressions: Vec<&Comparison> = comparisons.iter().filter(|c| c.regressed).collect();
+    if regressions.is_empty() {
+        out.push_str(&format!("\nNo regressions in {} rows.\n", comparisons.len()));
+    } else {
+        out.push_str(&format!("\n## {} regressions\n\n", regressions.len()));
+        table(&mut out, regressions);
+    }
+    let missing = unmatched(baseline, current);
+    if !missing.is_empty() {
+        out.push_str(&format!("\n## {} missing rows\n\nIn the baseline but not measured by this run.\n\n", missing.len()));
+        measurements(&mut out, missing, "baseline ns");
+    }
+    let new = unmatched(current, baseline);
+    if !new.is_empty() {
+        out.push_str(&format!("\n## {} new rows\n\nNot in the baseline, nothing to compare to.\n\n", new.len()));
+        measurements(&mut out, new, "current ns");
+    }
+    out.push_str("\n## All rows\n\n");
+    table(&mut out, comparisons.iter().collect());
+    out
+}
+
+fn table(out: &mut String, rows: Vec<&Comparison>) {
+    out.push_str("| fixture | format | operation | baseline ns | current ns | change |\n");
+    out.push_str("|---|---|---|---:|---:|---:|\n");
+    for c in rows {
+        let flag = if c.regressed { " **regressed**" } else { "" };
+        out.push_str(&format!("| {} | {} | {} | {:.1} | {:.1} | {:+.1}%{} |\n", c.fixture, c.format, c.operation, c.baseline_ns, c.current_ns, c.change * 100.0, flag));
+    }
+}
+
+fn measurements(out: &mut String, rows: Vec<&Measurement>, ns: &str) {
+    out.push_str(&format!("| fixture | format | operation | {} |\n", ns));
+    out.push_str("|---|---|---|---:|\n");
+    for m in rows {
+        out.push_str(&format!("| {} | {} | {} | {:.1} |\n", m.fixture, m.format, m.operation, m.ns));
+    }
+}
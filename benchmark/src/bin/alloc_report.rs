@@ -0,0 +1,21 @@
+// writes allocations.md and allocations.json, the heap use of every format
+// usage: alloc_report [output dir] (default target/alloc_report)
+use std::fs;
+use std::path::PathBuf;
+
+use benchmark::allocations::{alloc_rows, to_json, to_markdown, CountingAllocator};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or("target/alloc_report".to_string()));
+    fs::create_dir_all(&dir)?;
+
+    let rows = alloc_rows();
+    fs::write(dir.join("allocations.md"), to_markdown(&rows))?;
+    fs::write(dir.join("allocations.json"), to_json(&rows))?;
+
+    println!("{} rows written to {}", rows.len(), dir.display());
+    Ok(())
+}
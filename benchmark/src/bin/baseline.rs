@@ -0,0 +1,72 @@
+// stores a benchmark run as a baseline or compares a new run to it.
+// usage (build with --release): baseline save [baseline file]
+//                               baseline compare [baseline file] [threshold percent, default 10]
+// the times are this binary's own: baseline::run_suite times encode, decode and
+// access of every format on every fixture with a small median-of-samples loop.
+// they are not read from the criterion benches in benches/ and only compare
+// with other runs of this binary.
+// baseline file defaults to target/baseline/baseline.json. compare writes the new
+// run to current.json and the table to comparison.md next to the baseline, and
+// exits with 1 when any row is slower than the threshold or a baseline row was
+// not measured again
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use benchmark::baseline::{compare, run_suite, to_markdown, unmatched, Run};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let command = args.get(1).map(String::as_str).unwrap_or("");
+    let path = PathBuf::from(args.get(2).cloned().unwrap_or("target/baseline/baseline.json".to_string()));
+    let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+
+    match command {
+        "save" => {
+            let run = Run::new(run_suite());
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(&dir).unwrap();
+            }
+            fs::write(&path, run.to_json()).unwrap();
+            println!("{} results of {} saved to {}", run.results.len(), run.revision, path.display());
+            ExitCode::SUCCESS
+        }
+        "compare" => {
+            let threshold = match args.get(3).map(|t| t.parse::<f64>()) {
+                None => 0.10,
+                Some(Ok(percent)) => percent / 100.0,
+                Some(Err(_)) => {
+                    println!("threshold must be a number of percent");
+                    return ExitCode::from(2);
+                }
+            };
+            let baseline = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|json| Run::from_json(&json)) {
+                Ok(baseline) => baseline,
+                Err(e) => {
+                    println!("can not read baseline {}: {}", path.display(), e);
+                    return ExitCode::from(2);
+                }
+            };
+
+            let current = Run::new(run_suite());
+            let comparisons = compare(&baseline, &current, threshold);
+            let report = to_markdown(&baseline, &current, &comparisons, threshold);
+            fs::write(dir.join("current.json"), current.to_json()).unwrap();
+            fs::write(dir.join("comparison.md"), &report).unwrap();
+            print!("{}", report);
+
+            if comparisons.iter().any(|c| c.regressed) || !unmatched(&baseline, &current).is_empty() {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        _ => {
+            println!("usage: baseline save [baseline file]");
+            println!("       baseline compare [baseline file] [threshold percent]");
+            println!("times encode, decode and access of every format and fixture with its own loop,");
+            println!("not with criterion, so a baseline only compares with runs of this binary");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,18 @@
+// writes canonical.md and canonical.json, per format whether one value always
+// has one encoding. usage: canonical [output dir] (default target/canonical)
+use std::fs;
+use std::path::PathBuf;
+
+use benchmark::canonical::{to_json, to_markdown, verdicts};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or("target/canonical".to_string()));
+    fs::create_dir_all(&dir)?;
+
+    let verdicts = verdicts();
+    fs::write(dir.join("canonical.md"), to_markdown(&verdicts))?;
+    fs::write(dir.join("canonical.json"), to_json(&verdicts))?;
+
+    print!("{}", to_markdown(&verdicts));
+    Ok(())
+}
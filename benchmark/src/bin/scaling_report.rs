@@ -0,0 +1,21 @@
+// writes scaling.md and scaling.json, encode and decode throughput of every format
+// on 1..N threads. usage: scaling_report [output dir] [max threads]
+// (default target/scaling_report and every available thread), build with --release
+use std::fs;
+use std::path::PathBuf;
+
+use benchmark::scaling::{available_threads, scaling_rows, thread_counts, to_json, to_markdown};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or("target/scaling_report".to_string()));
+    let max = std::env::args().nth(2).and_then(|n| n.parse().ok()).unwrap_or(available_threads());
+    fs::create_dir_all(&dir)?;
+
+    let threads = thread_counts(max);
+    let rows = scaling_rows(&threads);
+    fs::write(dir.join("scaling.md"), to_markdown(&rows, &threads))?;
+    fs::write(dir.join("scaling.json"), to_json(&rows))?;
+
+    println!("{} rows for {:?} threads written to {}", rows.len(), threads, dir.display());
+    Ok(())
+}
@@ -0,0 +1,19 @@
+// writes evolution.md and evolution.json, which formats still read a record
+// after SampleData gains, loses or reorders a field
+// usage: schema_evolution [output dir] (default target/schema_evolution)
+use std::fs;
+use std::path::PathBuf;
+
+use benchmark::evolution::{evolution_matrix, to_json, to_markdown};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or("target/schema_evolution".to_string()));
+    fs::create_dir_all(&dir)?;
+
+    let cells = evolution_matrix();
+    fs::write(dir.join("evolution.md"), to_markdown(&cells))?;
+    fs::write(dir.join("evolution.json"), to_json(&cells))?;
+
+    print!("{}", to_markdown(&cells));
+    Ok(())
+}
@@ -0,0 +1,18 @@
+// writes sizes.md and sizes.json with the serialized size of every format
+// usage: size_report [output dir] (default target/size_report)
+use std::fs;
+use std::path::PathBuf;
+
+use benchmark::size_report::{size_rows, to_json, to_markdown};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or("target/size_report".to_string()));
+    fs::create_dir_all(&dir)?;
+
+    let rows = size_rows();
+    fs::write(dir.join("sizes.md"), to_markdown(&rows))?;
+    fs::write(dir.join("sizes.json"), to_json(&rows))?;
+
+    println!("{} sizes written to {}", rows.len(), dir.display());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// SampleData that points into the encoded bytes instead of owning its name and
+// values, so decoding it does not allocate. the wire format is the same as
+// SampleData, it reads bytes written by the owned type.
+// fields are Cow because not every format can hand out a slice of its input:
+// json escapes strings (the large fixture has newlines in its name) and json and
+// rmp write a Vec<u8> as an array of numbers. those fields come back owned
+use bincode::Options;
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::formats::{bincode_options, Bcs, Bincode, Json, Postcard, Rmp};
+use crate::{Format, SampleData};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SampleDataRef<'a> {
+    pub id: u32,
+
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+
+    pub active: bool,
+
+    #[serde(borrow, deserialize_with = "borrow_bytes")]
+    pub values: Cow<'a, [u8]>,
+}
+
+impl SampleDataRef<'_> {
+    pub fn to_owned_data(&self) -> SampleData {
+        SampleData { id: self.id, name: self.name.to_string(), active: self.active, values: self.values.to_vec() }
+    }
+
+    // which fields point into the input instead of owning a copy
+    pub fn borrowed(&self) -> (bool, bool) {
+        (matches!(self.name, Cow::Borrowed(_)), matches!(self.values, Cow::Borrowed(_)))
+    }
+}
+
+impl<'a> From<&'a SampleData> for SampleDataRef<'a> {
+    fn from(data: &'a SampleData) -> Self {
+        SampleDataRef { id: data.id, name: Cow::Borrowed(&data.name), active: data.active, values: Cow::Borrowed(&data.values) }
+    }
+}
+
+// serde's own Cow<[u8]> only takes byte strings, this also takes the array
+// SampleData's Vec<u8> is written as in self describing formats
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Cow<'de, [u8]>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes or an array of bytes")
+    }
+
+    fn visit_borrowed_bytes<E>(self, bytes: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(bytes))
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(bytes.to_vec()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+fn borrow_bytes<'de: 'a, 'a, D: Deserializer<'de>>(deserializer: D) -> Result<Cow<'a, [u8]>, D::Error> {
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+// serde formats that can deserialize from a borrowed slice
+pub trait BorrowedFormat: Format {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String>;
+}
+
+impl BorrowedFormat for Bincode {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+        bincode_options().deserialize(bytes).map_err(|e| e.to_string())
+    }
+}
+
+impl BorrowedFormat for Postcard {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+        match postcard::take_from_bytes(bytes) {
+            Ok((value, [])) => Ok(value),
+            Ok((_, rest)) => Err(format!("{} trailing bytes", rest.len())),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// rmp-serde only borrows from from_slice, which does not say where the value
+// ended, so unlike Rmp::decode trailing bytes are not caught here
+impl BorrowedFormat for Rmp {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+        rmp_serde::from_slice(bytes).map_err(|e| e.to_string())
+    }
+}
+
+impl BorrowedFormat for Json {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+        serde_json::from_slice(bytes).map_err(|e| e.to_string())
+    }
+}
+
+impl BorrowedFormat for Bcs {
+    fn decode_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, String> {
+        bcs::from_bytes(bytes).map_err(|e| e.to_string())
+    }
+}
+
+// called once per format that can borrow
+pub trait BorrowedVisitor {
+    fn visit<F: BorrowedFormat>(&mut self);
+}
+
+pub fn for_each_borrowed_format<V: BorrowedVisitor>(visitor: &mut V) {
+    visitor.visit::<Bincode>();
+    visitor.visit::<Postcard>();
+    visitor.visit::<Rmp>();
+    visitor.visit::<Json>();
+    visitor.visit::<Bcs>();
+}
@@ -0,0 +1,305 @@
+// canonical encoding checker. for data that is hashed or signed, one value must
+// have exactly one encoding: equal values give equal bytes, and any other
+// encoding of a value is rejected on decode. every check runs per format and
+// the format is only canonical when none of them fail
+use apache_avro::writer::datum::GenericDatumWriter;
+use apache_avro::AvroSchema;
+use borsh::{BorshDeserialize, BorshSerialize};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+
+use crate::evolution::SampleDataReordered;
+use crate::zero_copy::{Capnp, Codec, Flatbuffers, Plain, Rkyv, ZeroCopy};
+use crate::{for_each_format, for_each_zero_copy_format, AvroPayload, Format, FormatVisitor, SampleData, ZeroCopyFormat, ZeroCopyVisitor};
+
+// map and float, the two things SampleData does not have. price is optional so
+// formats without floats still get the map check
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message, Clone, PartialEq)]
+#[derive(rkyv::Archive, rkyv::Serialize, bitcode::Encode, apache_avro::AvroSchema)]
+pub struct Ledger {
+    #[prost(map = "string, uint64", tag = "1")]
+    pub balances: HashMap<String, u64>,
+
+    #[prost(double, optional, tag = "2")]
+    pub price: Option<f64>,
+}
+
+fn balances(capacity: usize, hasher: &RandomState) -> HashMap<String, u64> {
+    let mut balances = HashMap::with_capacity_and_hasher(capacity, hasher.clone());
+    balances.extend((0..32).map(|i| (format!("account{}", i), i)));
+    balances
+}
+
+// two equal ledgers whose maps iterate in different orders. both use one hasher,
+// a bigger table puts the keys in other buckets. the orders are compared, two new
+// maps could come out in the same order and a format that writes the map as it
+// iterates would pass. None when no table size gave another order
+fn ledgers() -> Option<(Ledger, Ledger)> {
+    let hasher = RandomState::new();
+    let first = balances(0, &hasher);
+    let second = (6..20).map(|bits| balances(1 << bits, &hasher)).find(|second| !second.keys().eq(first.keys()))?;
+    Some((Ledger { balances: first, price: None }, Ledger { balances: second, price: None }))
+}
+
+// every non canonical input below is written for this value
+pub fn sample() -> SampleData {
+    SampleData { id: 1, name: "a".to_string(), active: true, values: vec![1] }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    Pass,
+    Fail(String),
+    NotApplicable(String),
+}
+
+impl Check {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Check::Pass => "pass",
+            Check::Fail(_) => "fail",
+            Check::NotApplicable(_) => "n/a",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub check: Check,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Verdict {
+    pub format: String,
+    pub canonical: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+type LedgerEncoder = fn(&Ledger) -> Result<Vec<u8>, String>;
+
+// a non canonical input, Err when it can not be built and the check is n/a
+type Input = (&'static str, Result<Vec<u8>, String>);
+
+// an encode error means the format has no such type
+fn same_bytes(a: Result<Vec<u8>, String>, b: Result<Vec<u8>, String>, fail: &str) -> Check {
+    match (a, b) {
+        (Ok(a), Ok(b)) if a == b => Check::Pass,
+        (Ok(_), Ok(_)) => Check::Fail(fail.to_string()),
+        (Err(e), _) | (_, Err(e)) => Check::NotApplicable(e),
+    }
+}
+
+// rejected is a pass. accepted is fine only when re-encoding gives the same
+// bytes back, then the input was the canonical form after all
+fn rejects<C: Codec>(bytes: &[u8]) -> Check {
+    match C::decode::<SampleData>(bytes) {
+        Err(_) => Check::Pass,
+        Ok(value) if C::encode(&value) == bytes => Check::Pass,
+        Ok(value) => Check::Fail(format!("accepted as id {} name {:?} active {} values {:?}", value.id, value.name, value.active, value.values)),
+    }
+}
+
+fn verdict<C: Codec>(ledger_encoder: Option<LedgerEncoder>, non_canonical: Vec<Input>) -> Verdict {
+    let mut checks = Vec::new();
+    let mut add = |name: &str, check: Check| checks.push(CheckResult { name: name.to_string(), check });
+
+    match ledger_encoder {
+        Some(encode) => {
+            let map_order = match ledgers() {
+                Some((a, b)) => same_bytes(encode(&a), encode(&b), "same map, different bytes"),
+                None => Check::NotApplicable("no two map orders".to_string()),
+            };
+            add("map order", map_order);
+            let zero = Ledger { balances: HashMap::new(), price: Some(0.0) };
+            let negative = Ledger { balances: HashMap::new(), price: Some(-0.0) };
+            add("negative zero", same_bytes(encode(&zero), encode(&negative), "0.0 and -0.0 are equal but encode differently"));
+        }
+        None => {
+            add("map order", Check::NotApplicable("no map type".to_string()));
+            add("negative zero", Check::NotApplicable("no float type".to_string()));
+        }
+    }
+
+    // every decoder takes the whole input as one value and rejects anything
+    // after it, except flatbuffers, its buffers do not record where they end
+    let mut trailing = C::encode(&sample());
+    trailing.push(0);
+    add("trailing byte", rejects::<C>(&trailing));
+    for (name, bytes) in non_canonical {
+        add(name, bytes.map_or_else(Check::NotApplicable, |bytes| rejects::<C>(&bytes)));
+    }
+
+    let canonical = !checks.iter().any(|c| matches!(c.check, Check::Fail(_)));
+    Verdict { format: C::NAME.to_string(), canonical, checks }
+}
+
+// the sample written by the reordered v2 schema, same ids so it is the same value
+fn reordered() -> SampleDataReordered {
+    SampleDataReordered { values: vec![1], active: true, name: "a".to_string(), id: 1 }
+}
+
+// "a" is short enough to be stored inline in the archived string. an inline
+// string ends at the first 0xff, the bytes after that one are padding rkyv
+// does not look at. sample() with one of those bytes changed
+fn rkyv_name_padding() -> Result<Vec<u8>, String> {
+    let mut bytes = ZeroCopy::<Rkyv>::encode(&sample());
+    let archived = rkyv::access::<rkyv::Archived<SampleData>, rkyv::rancor::Error>(&bytes).map_err(|e| e.to_string())?;
+    let end = archived.name.as_ptr() as usize - bytes.as_ptr() as usize + archived.name.len();
+    if bytes.get(end..end + 2) != Some(&[0xff, 0xff][..]) {
+        return Err("name is not stored inline".to_string());
+    }
+    bytes[end + 1] = 0;
+    Ok(bytes)
+}
+
+// how each format encodes a Ledger and its non canonical encodings of sample(),
+// by Format::NAME. a format with no entry still gets the trailing byte check,
+// maps and floats are n/a for it
+fn inputs(format: &str) -> (Option<LedgerEncoder>, Vec<Input>) {
+    match format {
+        "bincode" => (Some(|l| bincode::serialize(l).map_err(|e| e.to_string())), vec![
+            ("bool byte 2", Ok(vec![1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, b'a', 2, 1, 0, 0, 0, 0, 0, 0, 0, 1])),
+        ]),
+        "bcs" => (Some(|l| bcs::to_bytes(l).map_err(|e| e.to_string())), vec![
+            ("overlong length", Ok(vec![1, 0, 0, 0, 0x81, 0x00, b'a', 1, 1, 1])),
+            ("bool byte 2", Ok(vec![1, 0, 0, 0, 1, b'a', 2, 1, 1])),
+        ]),
+        "serde_json" => (Some(|l| serde_json::to_vec(l).map_err(|e| e.to_string())), vec![
+            ("whitespace", Ok(br#"{ "id": 1, "name": "a", "active": true, "values": [1] }"#.to_vec())),
+            ("key order", Ok(br#"{"name":"a","id":1,"active":true,"values":[1]}"#.to_vec())),
+            ("escaped string", Ok(br#"{"id":1,"name":"\u0061","active":true,"values":[1]}"#.to_vec())),
+            ("exponent number", Ok(br#"{"id":1e0,"name":"a","active":true,"values":[1]}"#.to_vec())),
+            ("unknown field", Ok(br#"{"id":1,"name":"a","active":true,"values":[1],"x":0}"#.to_vec())),
+        ]),
+        "borsh" => (Some(|l| borsh::to_vec(l).map_err(|e| e.to_string())), vec![
+            ("bool byte 2", Ok(vec![1, 0, 0, 0, 1, 0, 0, 0, b'a', 2, 1, 0, 0, 0, 1])),
+        ]),
+        "rmp" => (Some(|l| rmp_serde::to_vec(l).map_err(|e| e.to_string())), vec![
+            ("wide int", Ok(vec![0x94, 0xcc, 0x01, 0xa1, b'a', 0xc3, 0x91, 0x01])),
+            ("str8 string", Ok(vec![0x94, 0x01, 0xd9, 0x01, b'a', 0xc3, 0x91, 0x01])),
+            ("map form", Ok([&[0x84, 0xa2][..], b"id", &[0x01, 0xa4], b"name", &[0xa1, b'a', 0xa6], b"active", &[0xc3, 0xa6], b"values", &[0x91, 0x01]].concat())),
+        ]),
+        "protobuf" => (Some(|l| Ok(l.encode_to_vec())), vec![
+            ("overlong varint", Ok(vec![0x08, 0x81, 0x00, 0x12, 0x01, b'a', 0x18, 0x01, 0x22, 0x01, 0x01])),
+            ("field order", Ok(vec![0x12, 0x01, b'a', 0x08, 0x01, 0x18, 0x01, 0x22, 0x01, 0x01])),
+            ("repeated field", Ok(vec![0x08, 0x05, 0x08, 0x01, 0x12, 0x01, b'a', 0x18, 0x01, 0x22, 0x01, 0x01])),
+            ("unknown field", Ok(vec![0x08, 0x01, 0x12, 0x01, b'a', 0x18, 0x01, 0x22, 0x01, 0x01, 0x28, 0x00])),
+        ]),
+        "rlp" => (None, vec![
+            ("single byte as string", Ok(vec![0xc5, 0x81, 0x01, b'a', 0x01, 0x01])),
+            ("long list header", Ok(vec![0xf8, 0x04, 0x01, b'a', 0x01, 0x01])),
+            ("leading zero", Ok(vec![0xc6, 0x82, 0x00, 0x01, b'a', 0x01, 0x01])),
+        ]),
+        "ssz" => (None, vec![
+            ("bool byte 2", Ok(vec![1, 0, 0, 0, 0x0d, 0, 0, 0, 2, 0x0e, 0, 0, 0, b'a', 1])),
+        ]),
+        "scale" => (None, vec![
+            ("overlong compact", Ok(vec![1, 0, 0, 0, 0x05, 0x00, b'a', 1, 4, 1])),
+            ("bool byte 2", Ok(vec![1, 0, 0, 0, 4, b'a', 2, 4, 1])),
+        ]),
+        "postcard" => (Some(|l| postcard::to_allocvec(l).map_err(|e| e.to_string())), vec![
+            ("overlong varint", Ok(vec![0x81, 0x00, 1, b'a', 1, 1, 1])),
+            ("bool byte 2", Ok(vec![1, 1, b'a', 2, 1, 1])),
+        ]),
+        // bitcode packs its own layout, there is no hand written input for it
+        "bitcode" => (Some(|l| Ok(bitcode::encode(l))), Vec::new()),
+        "cbor" => (
+            Some(|l| {
+                let mut out = Vec::new();
+                ciborium::into_writer(l, &mut out).map_err(|e| e.to_string())?;
+                Ok(out)
+            }),
+            vec![
+                ("wide int", Ok(cbor_sample(&[0x18, 0x01], &[0x61, b'a']))),
+                ("indefinite string", Ok(cbor_sample(&[0x01], &[0x7f, 0x61, b'a', 0xff]))),
+                ("key order", Ok([&[0xa4, 0x64][..], b"name", &[0x61, b'a', 0x62], b"id", &[0x01, 0x66], b"active", &[0xf5, 0x66], b"values", &[0x81, 0x01]].concat())),
+            ],
+        ),
+        "avro" => (
+            Some(|l| {
+                let schema = Ledger::get_schema();
+                GenericDatumWriter::builder(&schema).build().and_then(|w| w.write_ser_to_vec(l)).map_err(|e| e.to_string())
+            }),
+            vec![
+                ("overlong varint", Ok(avro_sample(&[0x82, 0x00, 0x02, b'a', 0x01, 0x02, 0x02, 0x00]))),
+                ("bool byte 2", Ok(avro_sample(&[0x02, 0x02, b'a', 0x02, 0x02, 0x02, 0x00]))),
+            ],
+        ),
+        "rkyv" => (Some(|l| rkyv::to_bytes::<rkyv::rancor::Error>(l).map(|b| b.to_vec()).map_err(|e| e.to_string())), vec![
+            ("string padding", rkyv_name_padding()),
+        ]),
+        // same value with the fields written in another order, the layout moves
+        "capnp" => (None, vec![("object order", Ok(ZeroCopy::<Capnp>::encode(&reordered())))]),
+        "flatbuffers" => (None, vec![("field order", Ok(ZeroCopy::<Flatbuffers>::encode(&reordered())))]),
+        _ => (None, Vec::new()),
+    }
+}
+
+// the cbor map of sample() with the given id and name items
+fn cbor_sample(id: &[u8], name: &[u8]) -> Vec<u8> {
+    [&[0xa4, 0x62][..], b"id", id, &[0x64], b"name", name, &[0x66], b"active", &[0xf5, 0x66], b"values", &[0x81, 0x01]].concat()
+}
+
+// a SampleData datum behind the single object header
+fn avro_sample(datum: &[u8]) -> Vec<u8> {
+    [&[0xc3, 0x01][..], &SampleData::avro_schema().fingerprint, datum].concat()
+}
+
+struct Verdicts(Vec<Verdict>);
+
+impl Verdicts {
+    fn add<C: Codec>(&mut self) {
+        let (ledger_encoder, non_canonical) = inputs(C::NAME);
+        self.0.push(verdict::<C>(ledger_encoder, non_canonical));
+    }
+}
+
+impl FormatVisitor for Verdicts {
+    fn visit<F: Format>(&mut self) {
+        self.add::<Plain<F>>();
+    }
+}
+
+impl ZeroCopyVisitor for Verdicts {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.add::<ZeroCopy<Z>>();
+    }
+}
+
+pub fn verdicts() -> Vec<Verdict> {
+    let mut verdicts = Verdicts(Vec::new());
+    for_each_format(&mut verdicts);
+    for_each_zero_copy_format(&mut verdicts);
+    verdicts.0
+}
+
+pub fn to_markdown(verdicts: &[Verdict]) -> String {
+    let mut out = String::from("# Canonical encoding\n\n");
+    out.push_str("A format passes a check when equal values give equal bytes, or when it rejects the non canonical input. ");
+    out.push_str("Inputs are hand written encodings of `SampleData { id: 1, name: \"a\", active: true, values: [1] }`.\n\n");
+    out.push_str("| format | verdict | failed checks |\n|---|---|---|\n");
+    for verdict in verdicts {
+        let failed: Vec<&str> = verdict.checks.iter().filter(|c| matches!(c.check, Check::Fail(_))).map(|c| c.name.as_str()).collect();
+        let label = if verdict.canonical { "canonical" } else { "not canonical" };
+        out.push_str(&format!("| {} | {} | {} |\n", verdict.format, label, failed.join(", ")));
+    }
+    out.push_str("\n## Checks\n\n| format | check | result | detail |\n|---|---|---|---|\n");
+    for verdict in verdicts {
+        for c in &verdict.checks {
+            let detail = match &c.check {
+                Check::Pass => "",
+                Check::Fail(detail) | Check::NotApplicable(detail) => detail,
+            };
+            out.push_str(&format!("| {} | {} | {} | {} |\n", verdict.format, c.name, c.check.label(), detail.replace('|', "\\|")));
+        }
+    }
+    out
+}
+
+pub fn to_json(verdicts: &[Verdict]) -> String {
+    serde_json::to_string_pretty(verdicts).unwrap()
+}
@@ -0,0 +1,294 @@
+// cap'n proto for the payloads, readers and builders in payloads_capnp.rs are
+// generated from schema/payloads.capnp, see there for how to regenerate them
+use capnp::message::{Builder, ReaderOptions};
+use capnp::serialize::{read_message_from_flat_slice_no_alloc, write_message_to_words};
+use capnp::traits::Owned;
+
+use crate::evolution::{SampleDataAdded, SampleDataRemoved, SampleDataReordered};
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::SampleData;
+
+#[path = "payloads_capnp.rs"]
+pub mod payloads_capnp;
+
+use payloads_capnp::{
+    block_header, event_log, merkle_proof, sample_data, sample_data_v2_added, sample_data_v2_removed, sample_data_v2_reordered,
+    signed_transaction,
+};
+
+pub trait CapnpRecord: Sized {
+    fn write_capnp(&self) -> Vec<u8>;
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String>;
+    // key field read straight from the message
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String>;
+}
+
+fn write<T: Owned>(fill: impl FnOnce(T::Builder<'_>)) -> Vec<u8> {
+    let mut message = Builder::new_default();
+    fill(message.init_root());
+    write_message_to_words(&message)
+}
+
+// the segment table gives the message length, anything after it is an error
+fn read<T: Owned, R>(bytes: &[u8], get: impl for<'a> FnOnce(T::Reader<'a>) -> capnp::Result<R>) -> Result<R, String> {
+    let mut rest = bytes;
+    let message = read_message_from_flat_slice_no_alloc(&mut rest, ReaderOptions::new()).map_err(|e| e.to_string())?;
+    if !rest.is_empty() {
+        return Err(format!("{} trailing bytes", rest.len()));
+    }
+    let root = message.get_root().map_err(|e| e.to_string())?;
+    get(root).map_err(|e| e.to_string())
+}
+
+fn set_data_list<T: AsRef<[u8]>>(mut list: capnp::data_list::Builder, values: &[T]) {
+    for (i, value) in values.iter().enumerate() {
+        list.set(i as u32, value.as_ref());
+    }
+}
+
+// capnp Data has no fixed length, hashes and addresses are checked when read
+fn fixed<const N: usize>(data: capnp::Result<&[u8]>) -> capnp::Result<[u8; N]> {
+    let data = data?;
+    data.try_into().map_err(|_| capnp::Error::failed(format!("expected {} bytes, got {}", N, data.len())))
+}
+
+fn fixed_list<const N: usize>(list: capnp::Result<capnp::data_list::Reader>) -> capnp::Result<Vec<[u8; N]>> {
+    list?.iter().map(fixed).collect()
+}
+
+impl CapnpRecord for SampleData {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<sample_data::Owned>(|mut b| {
+            b.set_id(self.id);
+            b.set_name(self.name.as_str());
+            b.set_active(self.active);
+            b.set_values(&self.values);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<sample_data::Owned, _>(bytes, |r| {
+            Ok(SampleData { id: r.get_id(), name: r.get_name()?.to_string()?, active: r.get_active(), values: r.get_values()?.to_vec() })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<sample_data::Owned, _>(bytes, |r| Ok(r.get_id() as u64))
+    }
+}
+
+impl CapnpRecord for SignedTransaction {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<signed_transaction::Owned>(|mut b| {
+            b.set_nonce(self.nonce);
+            b.set_gas_price(self.gas_price);
+            b.set_gas_limit(self.gas_limit);
+            b.set_to(&self.to);
+            b.set_value(&self.value);
+            b.set_data(&self.data);
+            b.set_v(self.v);
+            b.set_r(&self.r);
+            b.set_s(&self.s);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<signed_transaction::Owned, _>(bytes, |r| {
+            Ok(SignedTransaction {
+                nonce: r.get_nonce(),
+                gas_price: r.get_gas_price(),
+                gas_limit: r.get_gas_limit(),
+                to: fixed(r.get_to())?,
+                value: fixed(r.get_value())?,
+                data: r.get_data()?.to_vec(),
+                v: r.get_v(),
+                r: fixed(r.get_r())?,
+                s: fixed(r.get_s())?,
+            })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<signed_transaction::Owned, _>(bytes, |r| Ok(r.get_nonce()))
+    }
+}
+
+impl CapnpRecord for BlockHeader {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<block_header::Owned>(|mut b| {
+            b.set_parent_hash(&self.parent_hash);
+            b.set_uncles_hash(&self.uncles_hash);
+            b.set_coinbase(&self.coinbase);
+            b.set_state_root(&self.state_root);
+            b.set_transactions_root(&self.transactions_root);
+            b.set_receipts_root(&self.receipts_root);
+            b.set_logs_bloom(&self.logs_bloom);
+            b.set_difficulty(self.difficulty);
+            b.set_number(self.number);
+            b.set_gas_limit(self.gas_limit);
+            b.set_gas_used(self.gas_used);
+            b.set_timestamp(self.timestamp);
+            b.set_extra_data(&self.extra_data);
+            b.set_mix_hash(&self.mix_hash);
+            b.set_nonce(self.nonce);
+            b.set_base_fee_per_gas(self.base_fee_per_gas);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<block_header::Owned, _>(bytes, |r| {
+            Ok(BlockHeader {
+                parent_hash: fixed(r.get_parent_hash())?,
+                uncles_hash: fixed(r.get_uncles_hash())?,
+                coinbase: fixed(r.get_coinbase())?,
+                state_root: fixed(r.get_state_root())?,
+                transactions_root: fixed(r.get_transactions_root())?,
+                receipts_root: fixed(r.get_receipts_root())?,
+                logs_bloom: r.get_logs_bloom()?.to_vec(),
+                difficulty: r.get_difficulty(),
+                number: r.get_number(),
+                gas_limit: r.get_gas_limit(),
+                gas_used: r.get_gas_used(),
+                timestamp: r.get_timestamp(),
+                extra_data: r.get_extra_data()?.to_vec(),
+                mix_hash: fixed(r.get_mix_hash())?,
+                nonce: r.get_nonce(),
+                base_fee_per_gas: r.get_base_fee_per_gas(),
+            })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<block_header::Owned, _>(bytes, |r| Ok(r.get_number()))
+    }
+}
+
+impl CapnpRecord for EventLog {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<event_log::Owned>(|mut b| {
+            b.set_address(&self.address);
+            set_data_list(b.reborrow().init_topics(self.topics.len() as u32), &self.topics);
+            b.set_data(&self.data);
+            b.set_block_number(self.block_number);
+            b.set_transaction_hash(&self.transaction_hash);
+            b.set_log_index(self.log_index);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<event_log::Owned, _>(bytes, |r| {
+            Ok(EventLog {
+                address: fixed(r.get_address())?,
+                topics: fixed_list(r.get_topics())?,
+                data: r.get_data()?.to_vec(),
+                block_number: r.get_block_number(),
+                transaction_hash: fixed(r.get_transaction_hash())?,
+                log_index: r.get_log_index(),
+            })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<event_log::Owned, _>(bytes, |r| Ok(r.get_block_number()))
+    }
+}
+
+impl CapnpRecord for MerkleProof {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<merkle_proof::Owned>(|mut b| {
+            b.set_leaf(&self.leaf);
+            b.set_root(&self.root);
+            set_data_list(b.reborrow().init_siblings(self.siblings.len() as u32), &self.siblings);
+            b.set_index(self.index);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<merkle_proof::Owned, _>(bytes, |r| {
+            Ok(MerkleProof {
+                leaf: fixed(r.get_leaf())?,
+                root: fixed(r.get_root())?,
+                siblings: fixed_list(r.get_siblings())?,
+                index: r.get_index(),
+            })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<merkle_proof::Owned, _>(bytes, |r| Ok(r.get_index()))
+    }
+}
+
+// schema versions of SampleData, see SampleDataV2* in schema/payloads.capnp.
+// capnp has no optional scalars, chain_id is a value plus a has bit
+impl CapnpRecord for SampleDataAdded {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<sample_data_v2_added::Owned>(|mut b| {
+            b.set_id(self.id);
+            b.set_name(self.name.as_str());
+            b.set_active(self.active);
+            b.set_values(&self.values);
+            b.set_has_chain_id(self.chain_id.is_some());
+            b.set_chain_id(self.chain_id.unwrap_or_default());
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<sample_data_v2_added::Owned, _>(bytes, |r| {
+            Ok(SampleDataAdded {
+                id: r.get_id(),
+                name: r.get_name()?.to_string()?,
+                active: r.get_active(),
+                values: r.get_values()?.to_vec(),
+                chain_id: r.get_has_chain_id().then(|| r.get_chain_id()),
+            })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<sample_data_v2_added::Owned, _>(bytes, |r| Ok(r.get_id() as u64))
+    }
+}
+
+// a capnp field is never removed, obsoleteActive is just not written any more
+impl CapnpRecord for SampleDataRemoved {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<sample_data_v2_removed::Owned>(|mut b| {
+            b.set_id(self.id);
+            b.set_name(self.name.as_str());
+            b.set_values(&self.values);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<sample_data_v2_removed::Owned, _>(bytes, |r| {
+            Ok(SampleDataRemoved { id: r.get_id(), name: r.get_name()?.to_string()?, values: r.get_values()?.to_vec() })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<sample_data_v2_removed::Owned, _>(bytes, |r| Ok(r.get_id() as u64))
+    }
+}
+
+// ordinals decide the layout, so declaring the fields in another order changes nothing
+impl CapnpRecord for SampleDataReordered {
+    fn write_capnp(&self) -> Vec<u8> {
+        write::<sample_data_v2_reordered::Owned>(|mut b| {
+            b.set_values(&self.values);
+            b.set_active(self.active);
+            b.set_name(self.name.as_str());
+            b.set_id(self.id);
+        })
+    }
+
+    fn read_capnp(bytes: &[u8]) -> Result<Self, String> {
+        read::<sample_data_v2_reordered::Owned, _>(bytes, |r| {
+            Ok(SampleDataReordered { values: r.get_values()?.to_vec(), active: r.get_active(), name: r.get_name()?.to_string()?, id: r.get_id() })
+        })
+    }
+
+    fn capnp_key(bytes: &[u8]) -> Result<u64, String> {
+        read::<sample_data_v2_reordered::Owned, _>(bytes, |r| Ok(r.get_id() as u64))
+    }
+}
@@ -0,0 +1,265 @@
+// schema evolution: SampleData is v1, each type below is one v2 change. every
+// format writes one version and reads it back as the other, so we know which
+// formats are safe for archives that outlive the writer.
+// where a format has a schema (protobuf tags, capnp ordinals, flatbuffers ids,
+// avro record and field names) v2 follows its evolution rules, the others only
+// see the rust struct
+use borsh::{BorshDeserialize, BorshSerialize};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::ssz_string;
+use crate::formats::avro_schema;
+use crate::zero_copy::{record, Codec, Plain, ZeroCopy};
+use crate::{for_each_format, for_each_zero_copy_format, Format, FormatVisitor, Record, SampleData, ZeroCopyFormat, ZeroCopyVisitor};
+
+// v2 with a field added at the end, None when written by v1. every v2 keeps
+// the avro record name, resolution only matches records of the same name
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message, Clone, PartialEq)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+#[serde(rename = "SampleData")]
+pub struct SampleDataAdded {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+
+    #[prost(string, tag = "2")]
+    #[ssz(with = "ssz_string")]
+    pub name: String,
+
+    #[prost(bool, tag = "3")]
+    pub active: bool,
+
+    #[prost(bytes, tag = "4")]
+    pub values: Vec<u8>,
+
+    #[prost(uint64, optional, tag = "5")]
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+}
+
+// v2 without `active`, protobuf tag 3 is reserved
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message, Clone, PartialEq)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+#[serde(rename = "SampleData")]
+pub struct SampleDataRemoved {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+
+    #[prost(string, tag = "2")]
+    #[ssz(with = "ssz_string")]
+    pub name: String,
+
+    #[prost(bytes, tag = "4")]
+    pub values: Vec<u8>,
+}
+
+// v2 with the fields declared in reverse, tags and ids stay the same
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message, Clone, PartialEq)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+#[serde(rename = "SampleData")]
+pub struct SampleDataReordered {
+    #[prost(bytes, tag = "4")]
+    pub values: Vec<u8>,
+
+    #[prost(bool, tag = "3")]
+    pub active: bool,
+
+    #[prost(string, tag = "2")]
+    #[ssz(with = "ssz_string")]
+    pub name: String,
+
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+
+record!(SampleDataAdded, id);
+record!(SampleDataRemoved, id);
+record!(SampleDataReordered, id);
+avro_schema!(SampleDataAdded, SampleDataRemoved, SampleDataReordered);
+
+// fields of any version, None when that version does not have the field
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fields {
+    pub id: u32,
+    pub name: String,
+    pub active: Option<bool>,
+    pub values: Vec<u8>,
+    pub chain_id: Option<Option<u64>>,
+}
+
+impl Fields {
+    // what `read` must hold after decoding bytes written from `self`.
+    // a field only the reader has must come out empty
+    pub fn read_back_as(&self, read: &Fields) -> bool {
+        let active = match (self.active, read.active) {
+            (Some(written), Some(read)) => written == read,
+            _ => true,
+        };
+        let chain_id = match (self.chain_id, read.chain_id) {
+            (Some(written), Some(read)) => written == read,
+            (None, Some(read)) => read.is_none(),
+            _ => true,
+        };
+        self.id == read.id && self.name == read.name && self.values == read.values && active && chain_id
+    }
+}
+
+pub trait Version: Record {
+    const NAME: &'static str;
+    fn fields(&self) -> Fields;
+    fn sample() -> Self;
+}
+
+// values are all non default so a misread field shows up
+impl Version for SampleData {
+    const NAME: &'static str = "v1";
+    fn fields(&self) -> Fields {
+        Fields { id: self.id, name: self.name.clone(), active: Some(self.active), values: self.values.clone(), chain_id: None }
+    }
+    fn sample() -> Self {
+        SampleData { id: 7, name: "transfer".to_string(), active: true, values: vec![1, 2, 3] }
+    }
+}
+
+impl Version for SampleDataAdded {
+    const NAME: &'static str = "added field";
+    fn fields(&self) -> Fields {
+        Fields {
+            id: self.id,
+            name: self.name.clone(),
+            active: Some(self.active),
+            values: self.values.clone(),
+            chain_id: Some(self.chain_id),
+        }
+    }
+    fn sample() -> Self {
+        SampleDataAdded { id: 7, name: "transfer".to_string(), active: true, values: vec![1, 2, 3], chain_id: Some(1) }
+    }
+}
+
+impl Version for SampleDataRemoved {
+    const NAME: &'static str = "removed field";
+    fn fields(&self) -> Fields {
+        Fields { id: self.id, name: self.name.clone(), active: None, values: self.values.clone(), chain_id: None }
+    }
+    fn sample() -> Self {
+        SampleDataRemoved { id: 7, name: "transfer".to_string(), values: vec![1, 2, 3] }
+    }
+}
+
+impl Version for SampleDataReordered {
+    const NAME: &'static str = "reordered fields";
+    fn fields(&self) -> Fields {
+        Fields { id: self.id, name: self.name.clone(), active: Some(self.active), values: self.values.clone(), chain_id: None }
+    }
+    fn sample() -> Self {
+        SampleDataReordered { values: vec![1, 2, 3], active: true, name: "transfer".to_string(), id: 7 }
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Ok,
+    // decoded without error but a shared field changed, worst case for an archive
+    WrongValues,
+    Error(String),
+}
+
+impl Outcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Outcome::Ok => "ok",
+            Outcome::WrongValues => "wrong values",
+            Outcome::Error(_) => "error",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Cell {
+    pub format: String,
+    pub change: String,
+    pub old_reads_new: Outcome,
+    pub new_reads_old: Outcome,
+}
+
+// write W, read it as R
+fn outcome<C: Codec, W: Version, R: Version>() -> Outcome {
+    let written = W::sample();
+    match C::decode::<R>(&C::encode(&written)) {
+        Ok(read) if written.fields().read_back_as(&read.fields()) => Outcome::Ok,
+        Ok(_) => Outcome::WrongValues,
+        Err(e) => Outcome::Error(e),
+    }
+}
+
+fn cell<C: Codec, V: Version>() -> Cell {
+    Cell {
+        format: C::NAME.to_string(),
+        change: V::NAME.to_string(),
+        old_reads_new: outcome::<C, V, SampleData>(),
+        new_reads_old: outcome::<C, SampleData, V>(),
+    }
+}
+
+struct Matrix(Vec<Cell>);
+
+impl Matrix {
+    fn add<C: Codec>(&mut self) {
+        self.0.push(cell::<C, SampleDataAdded>());
+        self.0.push(cell::<C, SampleDataRemoved>());
+        self.0.push(cell::<C, SampleDataReordered>());
+    }
+}
+
+impl FormatVisitor for Matrix {
+    fn visit<F: Format>(&mut self) {
+        self.add::<Plain<F>>();
+    }
+}
+
+impl ZeroCopyVisitor for Matrix {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.add::<ZeroCopy<Z>>();
+    }
+}
+
+pub fn evolution_matrix() -> Vec<Cell> {
+    let mut matrix = Matrix(Vec::new());
+    for_each_format(&mut matrix);
+    for_each_zero_copy_format(&mut matrix);
+    matrix.0
+}
+
+pub fn to_markdown(cells: &[Cell]) -> String {
+    let changes = [SampleDataAdded::NAME, SampleDataRemoved::NAME, SampleDataReordered::NAME];
+    let mut out = String::from("# Schema evolution\n\n");
+    out.push_str("v1 is `SampleData`, each v2 makes one change. \"old reads new\" is a v1 reader on v2 bytes, ");
+    out.push_str("\"new reads old\" is a v2 reader on v1 bytes. \"wrong values\" means it decoded but a shared field changed.\n\n");
+    out.push_str("| format |");
+    for change in changes {
+        out.push_str(&format!(" {0}: old reads new | {0}: new reads old |", change));
+    }
+    out.push_str("\n|---|");
+    out.push_str(&"---|---|".repeat(changes.len()));
+    out.push('\n');
+    for row in cells.chunks(changes.len()) {
+        out.push_str(&format!("| {} |", row[0].format));
+        for cell in row {
+            out.push_str(&format!(" {} | {} |", cell.old_reads_new.label(), cell.new_reads_old.label()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+pub fn to_json(cells: &[Cell]) -> String {
+    serde_json::to_string_pretty(cells).unwrap()
+}
@@ -0,0 +1,244 @@
+// payloads modelled on what our tools move around: signed transactions,
+// block headers, event logs and merkle proofs, at a few sizes each.
+// hashes and addresses are fixed size arrays, prost's derive only takes Vec<u8>
+// for bytes so their protobuf Message is written by hand in proto.rs. serde
+// writes Vec<u8> and the arrays as byte strings in every format, the avro
+// schema says bytes and fixed to match. serde has no fixed size bytes, so
+// bincode, postcard and bcs write a length in front of every hash
+use apache_avro::serde::bytes::get_schema_in_ctxt as avro_bytes;
+use apache_avro::serde::fixed::get_schema_in_ctxt as avro_fixed;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::fixed_bytes_list;
+use crate::fixed_bytes_list::get_schema_in_ctxt as avro_fixed_list;
+use crate::proto::Proto;
+use crate::{sample_data, sample_data_large, Record, SampleData};
+
+// legacy signed transaction, like LocalWallet::sign_transaction + rlp_signed
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug, Default)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+pub struct SignedTransaction {
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<20>)]
+    pub to: [u8; 20],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub value: [u8; 32], // big endian
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_bytes)]
+    pub data: Vec<u8>,
+    pub v: u64,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub r: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub s: [u8; 32],
+}
+
+// post london block header
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug, Default)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+pub struct BlockHeader {
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub parent_hash: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub uncles_hash: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<20>)]
+    pub coinbase: [u8; 20],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub state_root: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub transactions_root: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub receipts_root: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_bytes)]
+    pub logs_bloom: Vec<u8>, // 256 bytes
+    pub difficulty: u64,
+    pub number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_bytes)]
+    pub extra_data: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub mix_hash: [u8; 32],
+    pub nonce: u64,
+    pub base_fee_per_gas: u64,
+}
+
+// log as returned by eth_getLogs, what event_listener stores
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug, Default)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+pub struct EventLog {
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<20>)]
+    pub address: [u8; 20],
+    #[serde(with = "fixed_bytes_list")]
+    #[avro(with = avro_fixed_list::<32>)]
+    pub topics: Vec<[u8; 32]>,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_bytes)]
+    pub data: Vec<u8>,
+    pub block_number: u64,
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub transaction_hash: [u8; 32],
+    pub log_index: u32,
+}
+
+// merkle proof as merkle_tree::abi exports it, sibling per level
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Clone, PartialEq, Debug, Default)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
+pub struct MerkleProof {
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub leaf: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    #[avro(with = avro_fixed::<32>)]
+    pub root: [u8; 32],
+    #[serde(with = "fixed_bytes_list")]
+    #[avro(with = avro_fixed_list::<32>)]
+    pub siblings: Vec<[u8; 32]>,
+    pub index: u64,
+}
+
+// deterministic filler bytes (xorshift), same every run
+pub fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut x = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+// bytes(seed, N) as a fixed size hash or address
+pub fn fixed<const N: usize>(seed: u64) -> [u8; N] {
+    bytes(seed, N).try_into().unwrap()
+}
+
+// u64 as a 32 byte big endian word, like a uint256 value
+pub fn word(value: u64) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+// n different records like an event export, for the batch and stream benches
+pub fn sample_records(n: usize) -> Vec<SampleData> {
+    (0..n)
+        .map(|i| SampleData {
+            id: i as u32,
+            name: format!("event{}", i),
+            active: i % 2 == 0,
+            values: bytes(i as u64, 32),
+        })
+        .collect()
+}
+
+// data_len 0 = plain transfer, 68 = erc20 transfer, bigger = contract call
+pub fn signed_transaction(data_len: usize) -> SignedTransaction {
+    SignedTransaction {
+        nonce: 42,
+        gas_price: 30_000_000_000,
+        gas_limit: 21_000 + 16 * data_len as u64,
+        to: fixed(1),
+        value: word(10_000_000_000_000_000),
+        data: bytes(2, data_len),
+        v: 2 * 11155111 + 35,
+        r: fixed(3),
+        s: fixed(4),
+    }
+}
+
+pub fn block_header(extra_data_len: usize) -> BlockHeader {
+    BlockHeader {
+        parent_hash: fixed(10),
+        uncles_hash: fixed(11),
+        coinbase: fixed(12),
+        state_root: fixed(13),
+        transactions_root: fixed(14),
+        receipts_root: fixed(15),
+        logs_bloom: bytes(16, 256),
+        difficulty: 0,
+        number: 19_000_000,
+        gas_limit: 30_000_000,
+        gas_used: 14_862_113,
+        timestamp: 1_705_000_000,
+        extra_data: bytes(17, extra_data_len),
+        mix_hash: fixed(18),
+        nonce: 0,
+        base_fee_per_gas: 21_000_000_000,
+    }
+}
+
+pub fn event_log(topics: usize, data_len: usize) -> EventLog {
+    EventLog {
+        address: fixed(20),
+        topics: (0..topics as u64).map(|i| fixed(21 + i)).collect(),
+        data: bytes(30, data_len),
+        block_number: 19_000_000,
+        transaction_hash: fixed(31),
+        log_index: 7,
+    }
+}
+
+pub fn merkle_proof(depth: usize) -> MerkleProof {
+    MerkleProof {
+        leaf: fixed(40),
+        root: fixed(41),
+        siblings: (0..depth as u64).map(|i| fixed(42 + i)).collect(),
+        index: (1u64 << depth.min(63)) / 3,
+    }
+}
+
+// fixtures also have a .proto message, for the generated protobuf benches, and
+// are shared between threads in the scaling benches
+pub trait Fixture: Record + Proto + Sync {}
+impl<T: Record + Proto + Sync> Fixture for T {}
+
+// called once per fixture with its concrete type
+pub trait FixtureVisitor {
+    fn visit<T: Fixture>(&mut self, name: &str, value: &T);
+}
+
+// every fixture the benchmarks and reports run on
+pub fn for_each_fixture<V: FixtureVisitor>(visitor: &mut V) {
+    visitor.visit("sample/small", &sample_data());
+    visitor.visit("sample/large", &sample_data_large());
+    visitor.visit("transaction/transfer", &signed_transaction(0));
+    visitor.visit("transaction/erc20", &signed_transaction(68));
+    visitor.visit("transaction/call_4k", &signed_transaction(4096));
+    visitor.visit("header/mainnet", &block_header(32));
+    visitor.visit("log/transfer", &event_log(3, 32));
+    visitor.visit("log/swap", &event_log(4, 256));
+    visitor.visit("log/blob_4k", &event_log(1, 4096));
+    visitor.visit("proof/depth_10", &merkle_proof(10));
+    visitor.visit("proof/depth_20", &merkle_proof(20));
+    visitor.visit("proof/depth_32", &merkle_proof(32));
+}
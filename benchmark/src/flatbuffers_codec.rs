@@ -0,0 +1,279 @@
+// flatbuffers for the payloads, tables are generated from schema/payloads.fbs
+// by planus in build.rs. planus checks every offset it follows, there is no
+// unchecked read and no separate verify pass
+use planus::{Builder, ReadAsRoot};
+use std::error::Error;
+
+use crate::evolution::{SampleDataAdded, SampleDataRemoved, SampleDataReordered};
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::SampleData;
+
+// planus writes the unformatted code on few long lines, clippy reads it as style issues
+#[allow(clippy::all)]
+pub mod payloads_fbs {
+    include!(concat!(env!("OUT_DIR"), "/payloads_fbs.rs"));
+}
+
+use payloads_fbs::payloads as fb;
+
+pub trait FlatbufferRecord: Sized {
+    fn write_flatbuffer(&self) -> Vec<u8>;
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String>;
+    // key field read straight from the buffer
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String>;
+}
+
+fn read<'a, T: ReadAsRoot<'a>, R>(bytes: &'a [u8], get: impl FnOnce(T) -> Result<R, Box<dyn Error>>) -> Result<R, String> {
+    get(T::read_as_root(bytes).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+fn bytes(value: Option<&[u8]>) -> Vec<u8> {
+    value.unwrap_or_default().to_vec()
+}
+
+// [ubyte] has no fixed length, hashes and addresses are checked when read
+fn fixed<const N: usize>(value: Option<&[u8]>) -> Result<[u8; N], Box<dyn Error>> {
+    let value = value.unwrap_or_default();
+    Ok(value.try_into().map_err(|_| format!("expected {} bytes, got {}", N, value.len()))?)
+}
+
+fn fixed_list<const N: usize>(list: Option<planus::Vector<planus::Result<fb::BytesRef>>>) -> Result<Vec<[u8; N]>, Box<dyn Error>> {
+    list.map(|list| list.iter().map(|item| fixed(item?.data()?)).collect()).unwrap_or(Ok(Vec::new()))
+}
+
+// flatbuffers has no vector of vectors, each item is a Bytes table
+fn create_list<T: AsRef<[u8]>>(b: &mut Builder, values: &[T]) -> Vec<planus::Offset<fb::Bytes>> {
+    values.iter().map(|value| fb::Bytes::create(b, value.as_ref())).collect()
+}
+
+impl FlatbufferRecord for SampleData {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::SampleData::create(&mut b, self.id, self.name.as_str(), self.active, self.values.as_slice());
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::SampleDataRef| {
+            Ok(SampleData { id: t.id()?, name: t.name()?.unwrap_or_default().to_string(), active: t.active()?, values: self::bytes(t.values()?) })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::SampleDataRef| Ok(t.id()? as u64))
+    }
+}
+
+impl FlatbufferRecord for SignedTransaction {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::SignedTransaction::create(
+            &mut b,
+            self.nonce,
+            self.gas_price,
+            self.gas_limit,
+            self.to.as_slice(),
+            self.value.as_slice(),
+            self.data.as_slice(),
+            self.v,
+            self.r.as_slice(),
+            self.s.as_slice(),
+        );
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::SignedTransactionRef| {
+            Ok(SignedTransaction {
+                nonce: t.nonce()?,
+                gas_price: t.gas_price()?,
+                gas_limit: t.gas_limit()?,
+                to: fixed(t.to()?)?,
+                value: fixed(t.value()?)?,
+                data: self::bytes(t.data()?),
+                v: t.v()?,
+                r: fixed(t.r()?)?,
+                s: fixed(t.s()?)?,
+            })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::SignedTransactionRef| Ok(t.nonce()?))
+    }
+}
+
+impl FlatbufferRecord for BlockHeader {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::BlockHeader::create(
+            &mut b,
+            self.parent_hash.as_slice(),
+            self.uncles_hash.as_slice(),
+            self.coinbase.as_slice(),
+            self.state_root.as_slice(),
+            self.transactions_root.as_slice(),
+            self.receipts_root.as_slice(),
+            self.logs_bloom.as_slice(),
+            self.difficulty,
+            self.number,
+            self.gas_limit,
+            self.gas_used,
+            self.timestamp,
+            self.extra_data.as_slice(),
+            self.mix_hash.as_slice(),
+            self.nonce,
+            self.base_fee_per_gas,
+        );
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::BlockHeaderRef| {
+            Ok(BlockHeader {
+                parent_hash: fixed(t.parent_hash()?)?,
+                uncles_hash: fixed(t.uncles_hash()?)?,
+                coinbase: fixed(t.coinbase()?)?,
+                state_root: fixed(t.state_root()?)?,
+                transactions_root: fixed(t.transactions_root()?)?,
+                receipts_root: fixed(t.receipts_root()?)?,
+                logs_bloom: self::bytes(t.logs_bloom()?),
+                difficulty: t.difficulty()?,
+                number: t.number()?,
+                gas_limit: t.gas_limit()?,
+                gas_used: t.gas_used()?,
+                timestamp: t.timestamp()?,
+                extra_data: self::bytes(t.extra_data()?),
+                mix_hash: fixed(t.mix_hash()?)?,
+                nonce: t.nonce()?,
+                base_fee_per_gas: t.base_fee_per_gas()?,
+            })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::BlockHeaderRef| Ok(t.number()?))
+    }
+}
+
+impl FlatbufferRecord for EventLog {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let topics = create_list(&mut b, &self.topics);
+        let root = fb::EventLog::create(
+            &mut b,
+            self.address.as_slice(),
+            topics.as_slice(),
+            self.data.as_slice(),
+            self.block_number,
+            self.transaction_hash.as_slice(),
+            self.log_index,
+        );
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::EventLogRef| {
+            Ok(EventLog {
+                address: fixed(t.address()?)?,
+                topics: fixed_list(t.topics()?)?,
+                data: self::bytes(t.data()?),
+                block_number: t.block_number()?,
+                transaction_hash: fixed(t.transaction_hash()?)?,
+                log_index: t.log_index()?,
+            })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::EventLogRef| Ok(t.block_number()?))
+    }
+}
+
+impl FlatbufferRecord for MerkleProof {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let siblings = create_list(&mut b, &self.siblings);
+        let root = fb::MerkleProof::create(&mut b, self.leaf.as_slice(), self.root.as_slice(), siblings.as_slice(), self.index);
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::MerkleProofRef| {
+            Ok(MerkleProof { leaf: fixed(t.leaf()?)?, root: fixed(t.root()?)?, siblings: fixed_list(t.siblings()?)?, index: t.index()? })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::MerkleProofRef| Ok(t.index()?))
+    }
+}
+
+// schema versions of SampleData, see SampleDataV2* in schema/payloads.fbs.
+// chain_id defaults to null, so it is only written when set
+impl FlatbufferRecord for SampleDataAdded {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::SampleDataV2Added::create(&mut b, self.id, self.name.as_str(), self.active, self.values.as_slice(), self.chain_id);
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::SampleDataV2AddedRef| {
+            Ok(SampleDataAdded {
+                id: t.id()?,
+                name: t.name()?.unwrap_or_default().to_string(),
+                active: t.active()?,
+                values: self::bytes(t.values()?),
+                chain_id: t.chain_id()?,
+            })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::SampleDataV2AddedRef| Ok(t.id()? as u64))
+    }
+}
+
+// the deprecated field keeps its id and gets no accessor
+impl FlatbufferRecord for SampleDataRemoved {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::SampleDataV2Removed::create(&mut b, self.id, self.name.as_str(), self.values.as_slice());
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::SampleDataV2RemovedRef| {
+            Ok(SampleDataRemoved { id: t.id()?, name: t.name()?.unwrap_or_default().to_string(), values: self::bytes(t.values()?) })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::SampleDataV2RemovedRef| Ok(t.id()? as u64))
+    }
+}
+
+// declared in reverse with explicit ids, the slots stay the same
+impl FlatbufferRecord for SampleDataReordered {
+    fn write_flatbuffer(&self) -> Vec<u8> {
+        let mut b = Builder::new();
+        let root = fb::SampleDataV2Reordered::create(&mut b, self.values.as_slice(), self.active, self.name.as_str(), self.id);
+        b.finish(root, None).to_vec()
+    }
+
+    fn read_flatbuffer(bytes: &[u8]) -> Result<Self, String> {
+        read(bytes, |t: fb::SampleDataV2ReorderedRef| {
+            Ok(SampleDataReordered {
+                values: self::bytes(t.values()?),
+                active: t.active()?,
+                name: t.name()?.unwrap_or_default().to_string(),
+                id: t.id()?,
+            })
+        })
+    }
+
+    fn flatbuffer_key(bytes: &[u8]) -> Result<u64, String> {
+        read(bytes, |t: fb::SampleDataV2ReorderedRef| Ok(t.id()? as u64))
+    }
+}
@@ -0,0 +1,288 @@
+// every serialization format under one trait so benches, reports and checks
+// can run each format on each fixture
+use apache_avro::rabin::Rabin;
+use apache_avro::reader::datum::GenericDatumReader;
+use apache_avro::writer::datum::GenericDatumWriter;
+use apache_avro::Schema;
+use bincode::Options;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::evolution::{SampleDataAdded, SampleDataRemoved, SampleDataReordered};
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::{Payload, SampleData};
+
+pub trait Format {
+    const NAME: &'static str;
+    fn encode<T: Payload>(value: &T) -> Vec<u8>;
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String>;
+}
+
+pub(crate) fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new().with_fixint_encoding().reject_trailing_bytes()
+}
+
+pub struct Bincode;
+impl Format for Bincode {
+    const NAME: &'static str = "bincode";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        bincode::serialize(value).unwrap()
+    }
+    // the options bincode::deserialize uses, minus allow_trailing_bytes
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        bincode_options().deserialize(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Bcs;
+impl Format for Bcs {
+    const NAME: &'static str = "bcs";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        bcs::to_bytes(value).unwrap()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        bcs::from_bytes(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Json;
+impl Format for Json {
+    const NAME: &'static str = "serde_json";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        serde_json::to_vec(value).unwrap()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        serde_json::from_slice(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Borsh;
+impl Format for Borsh {
+    const NAME: &'static str = "borsh";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        borsh::to_vec(value).unwrap()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        borsh::from_slice(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Rmp;
+impl Format for Rmp {
+    const NAME: &'static str = "rmp";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        rmp_serde::to_vec(value).unwrap()
+    }
+    // from_slice ignores what is left, reading from the slice leaves it behind
+    fn decode<T: Payload>(mut bytes: &[u8]) -> Result<T, String> {
+        let value = rmp_serde::from_read(&mut bytes).map_err(|e| e.to_string())?;
+        if !bytes.is_empty() {
+            return Err(format!("{} trailing bytes", bytes.len()));
+        }
+        Ok(value)
+    }
+}
+
+pub struct Protobuf;
+impl Format for Protobuf {
+    const NAME: &'static str = "protobuf";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        value.encode_to_vec()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        <T as prost::Message>::decode(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Rlp;
+impl Format for Rlp {
+    const NAME: &'static str = "rlp";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        rlp::encode(value).to_vec()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        rlp::decode(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Ssz;
+impl Format for Ssz {
+    const NAME: &'static str = "ssz";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        value.as_ssz_bytes()
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        T::from_ssz_bytes(bytes).map_err(|e| format!("{:?}", e))
+    }
+}
+
+pub struct Scale;
+impl Format for Scale {
+    const NAME: &'static str = "scale";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        parity_scale_codec::Encode::encode(value)
+    }
+    // decode_all so trailing bytes are an error like in the other formats
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        <T as parity_scale_codec::DecodeAll>::decode_all(&mut &bytes[..]).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Postcard;
+impl Format for Postcard {
+    const NAME: &'static str = "postcard";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).unwrap()
+    }
+    // from_bytes ignores what is left, take_from_bytes returns it
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        match postcard::take_from_bytes(bytes) {
+            Ok((value, [])) => Ok(value),
+            Ok((_, rest)) => Err(format!("{} trailing bytes", rest.len())),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// bitcode's own derive, its serde mode is slower
+pub struct Bitcode;
+impl Format for Bitcode {
+    const NAME: &'static str = "bitcode";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        bitcode::encode(value)
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        bitcode::decode(bytes).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Cbor;
+impl Format for Cbor {
+    const NAME: &'static str = "cbor";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        let mut out = Vec::new();
+        ciborium::into_writer(value, &mut out).unwrap();
+        out
+    }
+    fn decode<T: Payload>(mut bytes: &[u8]) -> Result<T, String> {
+        let value = ciborium::from_reader(&mut bytes).map_err(|e| e.to_string())?;
+        if !bytes.is_empty() {
+            return Err(format!("{} trailing bytes", bytes.len()));
+        }
+        Ok(value)
+    }
+}
+
+// avro needs the writer's schema to read anything, derived once per type
+// together with the rabin fingerprint it is registered under
+pub struct RegisteredSchema {
+    pub schema: Schema,
+    pub fingerprint: [u8; 8],
+}
+
+impl RegisteredSchema {
+    pub fn new(schema: Schema) -> Self {
+        let fingerprint = schema.fingerprint::<Rabin>().bytes.try_into().unwrap();
+        RegisteredSchema { schema, fingerprint }
+    }
+}
+
+pub trait AvroPayload {
+    fn avro_schema() -> &'static RegisteredSchema;
+}
+
+macro_rules! avro_schema {
+    ($($ty:ty),*) => {
+        $(impl $crate::formats::AvroPayload for $ty {
+            fn avro_schema() -> &'static $crate::formats::RegisteredSchema {
+                static SCHEMA: std::sync::OnceLock<$crate::formats::RegisteredSchema> = std::sync::OnceLock::new();
+                SCHEMA.get_or_init(|| $crate::formats::RegisteredSchema::new(<$ty as apache_avro::AvroSchema>::get_schema()))
+            }
+        })*
+    };
+}
+
+pub(crate) use avro_schema;
+
+avro_schema!(SampleData, SignedTransaction, BlockHeader, EventLog, MerkleProof);
+
+// every schema this crate writes by fingerprint, what a consumer would fetch
+// from the schema registry
+fn avro_registry() -> &'static HashMap<[u8; 8], &'static Schema> {
+    static REGISTRY: OnceLock<HashMap<[u8; 8], &'static Schema>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        [
+            SampleData::avro_schema(),
+            SignedTransaction::avro_schema(),
+            BlockHeader::avro_schema(),
+            EventLog::avro_schema(),
+            MerkleProof::avro_schema(),
+            SampleDataAdded::avro_schema(),
+            SampleDataRemoved::avro_schema(),
+            SampleDataReordered::avro_schema(),
+        ]
+        .into_iter()
+        .map(|registered| (registered.fingerprint, &registered.schema))
+        .collect()
+    })
+}
+
+// single object encoding: c3 01, the writer schema's fingerprint, then the
+// datum without the object container file header. the reader looks the writer
+// schema up in the registry and resolves it against its own, like a consumer
+// in a schema registry pipeline. serde goes straight to the bytes through the
+// schema, only resolving between two different schemas goes through avro Value
+pub struct Avro;
+impl Format for Avro {
+    const NAME: &'static str = "avro";
+    fn encode<T: Payload>(value: &T) -> Vec<u8> {
+        let writer = GenericDatumWriter::builder(&T::avro_schema().schema).build().unwrap();
+        let mut out = vec![0xc3, 0x01];
+        out.extend_from_slice(&T::avro_schema().fingerprint);
+        writer.write_ser(&mut out, value).unwrap();
+        out
+    }
+    fn decode<T: Payload>(bytes: &[u8]) -> Result<T, String> {
+        let (fingerprint, mut bytes) = match bytes {
+            [0xc3, 0x01, rest @ ..] if rest.len() >= 8 => rest.split_at(8),
+            _ => return Err("no avro single object header".to_string()),
+        };
+        let writer = avro_registry().get(fingerprint).ok_or("writer schema is not in the registry")?;
+        let reader = &T::avro_schema().schema;
+        let value = if fingerprint == T::avro_schema().fingerprint {
+            GenericDatumReader::builder(reader).build().and_then(|r| r.read_deser(&mut bytes))
+        } else {
+            GenericDatumReader::builder(writer)
+                .reader_schema(reader)
+                .build()
+                .and_then(|r| r.read_value(&mut bytes))
+                .and_then(|value| apache_avro::from_value(&value))
+        }
+        .map_err(|e| e.to_string())?;
+        if !bytes.is_empty() {
+            return Err(format!("{} trailing bytes", bytes.len()));
+        }
+        Ok(value)
+    }
+}
+
+// called once per format
+pub trait FormatVisitor {
+    fn visit<F: Format>(&mut self);
+}
+
+pub fn for_each_format<V: FormatVisitor>(visitor: &mut V) {
+    visitor.visit::<Bincode>();
+    visitor.visit::<Bcs>();
+    visitor.visit::<Json>();
+    visitor.visit::<Borsh>();
+    visitor.visit::<Rmp>();
+    visitor.visit::<Protobuf>();
+    visitor.visit::<Rlp>();
+    visitor.visit::<Ssz>();
+    visitor.visit::<Scale>();
+    visitor.visit::<Postcard>();
+    visitor.visit::<Bitcode>();
+    visitor.visit::<Cbor>();
+    visitor.visit::<Avro>();
+}
@@ -1,36 +1,146 @@
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 use borsh::{BorshSerialize, BorshDeserialize};
 use prost::Message;
 
-#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message )]
+pub mod fixtures;
+pub mod formats;
+pub mod rlp_codec;
+pub mod capnp_codec;
+pub mod flatbuffers_codec;
+pub mod zero_copy;
+pub mod size_report;
+pub mod evolution;
+pub mod proto;
+pub mod canonical;
+pub mod allocations;
+pub mod stream;
+pub mod baseline;
+pub mod scaling;
+pub mod borrowed;
+pub use fixtures::{for_each_fixture, Fixture, FixtureVisitor};
+pub use formats::{for_each_format, AvroPayload, Format, FormatVisitor};
+pub use zero_copy::{for_each_zero_copy_format, Codec, Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Message, Clone, PartialEq)]
+#[derive(ssz_derive::Encode, ssz_derive::Decode, parity_scale_codec::Encode, parity_scale_codec::Decode)]
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[derive(bitcode::Encode, bitcode::Decode, apache_avro::AvroSchema)]
 pub struct SampleData {
     #[prost(uint32, tag = "1")]
     pub id: u32,
-    
+
     #[prost(string, tag = "2")]
+    #[ssz(with = "ssz_string")]
     pub name: String,
-    
+
     #[prost(bool, tag = "3")]
     pub active: bool,
-    
+
     #[prost(bytes, tag = "4")]
     pub values: Vec<u8>,
 }
 
+// anything every format can encode, fixtures implement it through the derives
+// (rlp by hand in rlp_codec, the avro schema through avro_schema!)
+pub trait Payload:
+    Serialize + DeserializeOwned + BorshSerialize + BorshDeserialize + Message
+    + rlp::Encodable + rlp::Decodable + ssz::Encode + ssz::Decode
+    + parity_scale_codec::Encode + parity_scale_codec::Decode
+    + bitcode::Encode + bitcode::DecodeOwned + AvroPayload
+    + Default + Clone + PartialEq
+{
+}
+impl<T> Payload for T where
+    T: Serialize + DeserializeOwned + BorshSerialize + BorshDeserialize + Message
+        + rlp::Encodable + rlp::Decodable + ssz::Encode + ssz::Decode
+        + parity_scale_codec::Encode + parity_scale_codec::Decode
+        + bitcode::Encode + bitcode::DecodeOwned + AvroPayload
+        + Default + Clone + PartialEq
+{
+}
+
+// Vec<[u8; N]> as a list of byte strings, serde_bytes only takes a single
+// array. the avro schema is an array of fixed to match
+pub mod fixed_bytes_list {
+    use apache_avro::schema::{Name, NamespaceRef};
+    use apache_avro::Schema;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashSet;
+
+    struct Item<const N: usize>([u8; N]);
+
+    impl<const N: usize> Serialize for Item<N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serde_bytes::serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de, const N: usize> Deserialize<'de> for Item<N> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            serde_bytes::deserialize(deserializer).map(Item)
+        }
+    }
+
+    pub fn serialize<S: Serializer, const N: usize>(values: &[[u8; N]], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| Item(*value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<Vec<[u8; N]>, D::Error> {
+        Ok(Vec::<Item<N>>::deserialize(deserializer)?.into_iter().map(|item| item.0).collect())
+    }
+
+    pub fn get_schema_in_ctxt<const N: usize>(named_schemas: &mut HashSet<Name>, enclosing_namespace: NamespaceRef) -> Schema {
+        Schema::array(apache_avro::serde::fixed::get_schema_in_ctxt::<N>(named_schemas, enclosing_namespace)).build()
+    }
+}
+
+// ssz has no string type, encode it as utf8 bytes (List[uint8])
+pub mod ssz_string {
+    pub mod encode {
+        pub fn is_ssz_fixed_len() -> bool {
+            false
+        }
+        pub fn ssz_fixed_len() -> usize {
+            ssz::BYTES_PER_LENGTH_OFFSET
+        }
+        pub fn ssz_bytes_len(value: &str) -> usize {
+            value.len()
+        }
+        pub fn ssz_append(value: &str, buf: &mut Vec<u8>) {
+            buf.extend_from_slice(value.as_bytes());
+        }
+    }
+
+    pub mod decode {
+        use ssz::DecodeError;
 
-// pub fn sample_data() -> SampleData {
-//     SampleData {
-//         id: 1231566666,
-//         name: "Hi My Name is Rohit i am from India and i am learning Rust adfdnfsdnmfnsmdfmsdfmnsmdnfsndfs
-//         sdfnksndfnskdfjksdfknsdfnksdnfksjdkfjskdfjksdjfjsdfkjsdkfjksdjfkjsdfjsdfjdfmsndf skdf dfsdmf sd
-//         ksjdkfksdfnskdnfnsdkfnksdfksdkfnskdnfs dfksdfksndknsdfnskdnfksndfknsdkfnsdfnsd
-//         Hi My Name is Rohit i am from India and i am learning Rust adfdnfsdnmfnsmdfmsdfmnsmdnfsndfs
-//         sdfnksndfnskdfjksdfknsdfnksdnfksjdkfjskdfjksdjfjsdfkjsdkfjksdjfkjsdfjsdfjdfmsndf skdf dfsdmf sd
-//         ksjdkfksdfnskdnfnsdkfnksdfksdkfnskdnfs dfksdfksndknsdfnskdnfksndfknsdkfnsdfnsd".to_string(),
-//         active: true,
-//         values: vec![1,2,3,4,5,6,7,8,9,10,23,1,2,3,4,5,5,6,7,8,9,63,3,3,3,4,5,5,6,7,7,8,9,9,91,2,2,34,4,5,5,6,7,78] 
-//     }
-// }
+        pub fn is_ssz_fixed_len() -> bool {
+            false
+        }
+        pub fn ssz_fixed_len() -> usize {
+            ssz::BYTES_PER_LENGTH_OFFSET
+        }
+        pub fn from_ssz_bytes(bytes: &[u8]) -> Result<String, DecodeError> {
+            String::from_utf8(bytes.to_vec()).map_err(|e| DecodeError::BytesInvalid(e.to_string()))
+        }
+    }
+}
+
+
+pub fn sample_data_large() -> SampleData {
+    SampleData {
+        id: 1231566666,
+        name: "Hi My Name is Rohit i am from India and i am learning Rust adfdnfsdnmfnsmdfmsdfmnsmdnfsndfs
+        sdfnksndfnskdfjksdfknsdfnksdnfksjdkfjskdfjksdjfjsdfkjsdkfjksdjfkjsdfjsdfjdfmsndf skdf dfsdmf sd
+        ksjdkfksdfnskdnfnsdkfnksdfksdkfnskdnfs dfksdfksndknsdfnskdnfksndfknsdkfnsdfnsd
+        Hi My Name is Rohit i am from India and i am learning Rust adfdnfsdnmfnsmdfmsdfmnsmdnfsndfs
+        sdfnksndfnskdfjksdfknsdfnksdnfksjdkfjskdfjksdjfjsdfkjsdkfjksdjfkjsdfjsdfjdfmsndf skdf dfsdmf sd
+        ksjdkfksdfnskdnfnsdkfnksdfksdkfnskdnfs dfksdfksndknsdfnskdnfksndfknsdkfnsdfnsd".to_string(),
+        active: true,
+        values: vec![1,2,3,4,5,6,7,8,9,10,23,1,2,3,4,5,5,6,7,8,9,63,3,3,3,4,5,5,6,7,7,8,9,9,91,2,2,34,4,5,5,6,7,78]
+    }
+}
 
 pub fn sample_data() -> SampleData {
     SampleData {
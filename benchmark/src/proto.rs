@@ -0,0 +1,207 @@
+// types generated by build.rs from proto/payloads.proto, once by prost-build and
+// once by rust-protobuf, and the conversion from our payloads into both.
+// also the prost Message of the fixtures, written by hand because the derive
+// has no fixed size bytes
+use prost::bytes::{Buf, BufMut};
+use prost::encoding::{check_wire_type, decode_varint, encode_key, encode_varint, encoded_len_varint, key_len, skip_field};
+use prost::encoding::{DecodeContext, WireType};
+use prost::DecodeError;
+
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::SampleData;
+
+pub mod prost_generated {
+    include!(concat!(env!("OUT_DIR"), "/prost/payloads.rs"));
+}
+
+pub mod rust_protobuf {
+    include!(concat!(env!("OUT_DIR"), "/rust_protobuf/mod.rs"));
+}
+
+pub trait Proto {
+    type Prost: prost::Message + Default + PartialEq;
+    type RustProtobuf: protobuf::Message + PartialEq;
+    fn to_prost(&self) -> Self::Prost;
+    fn to_rust_protobuf(&self) -> Self::RustProtobuf;
+}
+
+// our field type as the generated one, fixed size bytes become Vec<u8>
+trait Generated {
+    type Out;
+    fn generated(self) -> Self::Out;
+}
+
+macro_rules! generated_as_is {
+    ($($ty:ty),*) => {
+        $(impl Generated for $ty {
+            type Out = $ty;
+            fn generated(self) -> $ty {
+                self
+            }
+        })*
+    };
+}
+
+generated_as_is!(u32, u64, bool, String, Vec<u8>);
+
+impl<const N: usize> Generated for [u8; N] {
+    type Out = Vec<u8>;
+    fn generated(self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> Generated for Vec<[u8; N]> {
+    type Out = Vec<Vec<u8>>;
+    fn generated(self) -> Vec<Vec<u8>> {
+        self.iter().map(|bytes| bytes.to_vec()).collect()
+    }
+}
+
+// generated fields have the same names as ours
+macro_rules! proto {
+    ($ty:ident, $($field:ident),*) => {
+        impl Proto for $ty {
+            type Prost = prost_generated::$ty;
+            type RustProtobuf = rust_protobuf::payloads::$ty;
+
+            fn to_prost(&self) -> Self::Prost {
+                let $ty { $($field),* } = self.clone();
+                prost_generated::$ty { $($field: $field.generated()),* }
+            }
+
+            fn to_rust_protobuf(&self) -> Self::RustProtobuf {
+                let $ty { $($field),* } = self.clone();
+                rust_protobuf::payloads::$ty { $($field: $field.generated(),)* ..Default::default() }
+            }
+        }
+    };
+}
+
+proto!(SampleData, id, name, active, values);
+proto!(SignedTransaction, nonce, gas_price, gas_limit, to, value, data, v, r, s);
+proto!(
+    BlockHeader, parent_hash, uncles_hash, coinbase, state_root, transactions_root, receipts_root, logs_bloom,
+    difficulty, number, gas_limit, gas_used, timestamp, extra_data, mix_hash, nonce, base_fee_per_gas
+);
+proto!(EventLog, address, topics, data, block_number, transaction_hash, log_index);
+proto!(MerkleProof, leaf, root, siblings, index);
+
+// one field of a hand written Message, encoded the way prost's derive does it:
+// proto3 scalars and bytes are left out when empty
+trait Field {
+    fn encode(&self, tag: u32, buf: &mut impl BufMut);
+    fn merge(&mut self, wire_type: WireType, buf: &mut impl Buf, ctx: DecodeContext) -> Result<(), DecodeError>;
+    fn encoded_len(&self, tag: u32) -> usize;
+}
+
+macro_rules! scalar_field {
+    ($($ty:ty => $module:ident),*) => {
+        $(impl Field for $ty {
+            fn encode(&self, tag: u32, buf: &mut impl BufMut) {
+                if *self != 0 {
+                    prost::encoding::$module::encode(tag, self, buf);
+                }
+            }
+            fn merge(&mut self, wire_type: WireType, buf: &mut impl Buf, ctx: DecodeContext) -> Result<(), DecodeError> {
+                prost::encoding::$module::merge(wire_type, self, buf, ctx)
+            }
+            fn encoded_len(&self, tag: u32) -> usize {
+                if *self != 0 { prost::encoding::$module::encoded_len(tag, self) } else { 0 }
+            }
+        })*
+    };
+}
+
+scalar_field!(u32 => uint32, u64 => uint64);
+
+impl Field for Vec<u8> {
+    fn encode(&self, tag: u32, buf: &mut impl BufMut) {
+        if !self.is_empty() {
+            prost::encoding::bytes::encode(tag, self, buf);
+        }
+    }
+    fn merge(&mut self, wire_type: WireType, buf: &mut impl Buf, ctx: DecodeContext) -> Result<(), DecodeError> {
+        prost::encoding::bytes::merge(wire_type, self, buf, ctx)
+    }
+    fn encoded_len(&self, tag: u32) -> usize {
+        if !self.is_empty() { prost::encoding::bytes::encoded_len(tag, self) } else { 0 }
+    }
+}
+
+// plain bytes on the wire, any other length is an error when reading
+impl<const N: usize> Field for [u8; N] {
+    fn encode(&self, tag: u32, buf: &mut impl BufMut) {
+        encode_key(tag, WireType::LengthDelimited, buf);
+        encode_varint(N as u64, buf);
+        buf.put_slice(self);
+    }
+    fn merge(&mut self, wire_type: WireType, buf: &mut impl Buf, _ctx: DecodeContext) -> Result<(), DecodeError> {
+        check_wire_type(WireType::LengthDelimited, wire_type)?;
+        let len = decode_varint(buf)?;
+        if len != N as u64 {
+            return Err(DecodeError::new(format!("expected {} bytes, got {}", N, len)));
+        }
+        if buf.remaining() < N {
+            return Err(DecodeError::new("buffer underflow"));
+        }
+        buf.copy_to_slice(self);
+        Ok(())
+    }
+    fn encoded_len(&self, tag: u32) -> usize {
+        key_len(tag) + encoded_len_varint(N as u64) + N
+    }
+}
+
+// repeated bytes, one entry per item
+impl<const N: usize> Field for Vec<[u8; N]> {
+    fn encode(&self, tag: u32, buf: &mut impl BufMut) {
+        for item in self {
+            Field::encode(item, tag, buf);
+        }
+    }
+    fn merge(&mut self, wire_type: WireType, buf: &mut impl Buf, ctx: DecodeContext) -> Result<(), DecodeError> {
+        let mut item = [0; N];
+        Field::merge(&mut item, wire_type, buf, ctx)?;
+        self.push(item);
+        Ok(())
+    }
+    fn encoded_len(&self, tag: u32) -> usize {
+        self.iter().map(|item| Field::encoded_len(item, tag)).sum()
+    }
+}
+
+// tags as in proto/payloads.proto
+macro_rules! message {
+    ($ty:ident, $($tag:literal => $field:ident),*) => {
+        impl prost::Message for $ty {
+            fn encode_raw(&self, buf: &mut impl BufMut) {
+                $(Field::encode(&self.$field, $tag, buf);)*
+            }
+
+            fn merge_field(&mut self, tag: u32, wire_type: WireType, buf: &mut impl Buf, ctx: DecodeContext) -> Result<(), DecodeError> {
+                match tag {
+                    $($tag => Field::merge(&mut self.$field, wire_type, buf, ctx),)*
+                    _ => skip_field(wire_type, tag, buf, ctx),
+                }
+            }
+
+            fn encoded_len(&self) -> usize {
+                0 $(+ Field::encoded_len(&self.$field, $tag))*
+            }
+
+            fn clear(&mut self) {
+                *self = Self::default();
+            }
+        }
+    };
+}
+
+message!(SignedTransaction, 1 => nonce, 2 => gas_price, 3 => gas_limit, 4 => to, 5 => value, 6 => data, 7 => v, 8 => r, 9 => s);
+message!(
+    BlockHeader, 1 => parent_hash, 2 => uncles_hash, 3 => coinbase, 4 => state_root, 5 => transactions_root,
+    6 => receipts_root, 7 => logs_bloom, 8 => difficulty, 9 => number, 10 => gas_limit, 11 => gas_used,
+    12 => timestamp, 13 => extra_data, 14 => mix_hash, 15 => nonce, 16 => base_fee_per_gas
+);
+message!(EventLog, 1 => address, 2 => topics, 3 => data, 4 => block_number, 5 => transaction_hash, 6 => log_index);
+message!(MerkleProof, 1 => leaf, 2 => root, 3 => siblings, 4 => index);
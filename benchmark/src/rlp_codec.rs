@@ -0,0 +1,238 @@
+// ethereum RLP for the payloads, written by hand because rlp-derive encode
+// Vec<u8> as a list of single byte items instead of a byte string
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+use crate::evolution::{SampleDataAdded, SampleDataRemoved, SampleDataReordered};
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::SampleData;
+
+// every payload is a list, reject lists with missing or extra items
+fn check_len(rlp: &Rlp, len: usize) -> Result<(), DecoderError> {
+    if rlp.item_count()? != len {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+    Ok(())
+}
+
+// hashes and addresses are byte strings of exactly N bytes
+fn fixed_value<const N: usize>(rlp: &Rlp) -> Result<[u8; N], DecoderError> {
+    rlp.decoder().decode_value(|bytes| bytes.try_into().map_err(|_| DecoderError::RlpInvalidLength))
+}
+
+fn fixed<const N: usize>(rlp: &Rlp, index: usize) -> Result<[u8; N], DecoderError> {
+    fixed_value(&rlp.at(index)?)
+}
+
+fn fixed_list<const N: usize>(rlp: &Rlp, index: usize) -> Result<Vec<[u8; N]>, DecoderError> {
+    let list = rlp.at(index)?;
+    if !list.is_list() {
+        return Err(DecoderError::RlpExpectedToBeList);
+    }
+    list.iter().map(|item| fixed_value(&item)).collect()
+}
+
+fn append_fixed_list<const N: usize>(s: &mut RlpStream, items: &[[u8; N]]) {
+    s.begin_list(items.len());
+    for item in items {
+        s.append(&item.as_slice());
+    }
+}
+
+impl Encodable for SampleData {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.id);
+        s.append(&self.name);
+        s.append(&self.active);
+        s.append(&self.values);
+    }
+}
+
+impl Decodable for SampleData {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 4)?;
+        Ok(SampleData { id: rlp.val_at(0)?, name: rlp.val_at(1)?, active: rlp.val_at(2)?, values: rlp.val_at(3)? })
+    }
+}
+
+// same field order as the signed legacy transaction
+impl Encodable for SignedTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(9);
+        s.append(&self.nonce);
+        s.append(&self.gas_price);
+        s.append(&self.gas_limit);
+        s.append(&self.to.as_slice());
+        s.append(&self.value.as_slice());
+        s.append(&self.data);
+        s.append(&self.v);
+        s.append(&self.r.as_slice());
+        s.append(&self.s.as_slice());
+    }
+}
+
+impl Decodable for SignedTransaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 9)?;
+        Ok(SignedTransaction {
+            nonce: rlp.val_at(0)?,
+            gas_price: rlp.val_at(1)?,
+            gas_limit: rlp.val_at(2)?,
+            to: fixed(rlp, 3)?,
+            value: fixed(rlp, 4)?,
+            data: rlp.val_at(5)?,
+            v: rlp.val_at(6)?,
+            r: fixed(rlp, 7)?,
+            s: fixed(rlp, 8)?,
+        })
+    }
+}
+
+// same field order as the header that is hashed into the block hash
+impl Encodable for BlockHeader {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(16);
+        s.append(&self.parent_hash.as_slice());
+        s.append(&self.uncles_hash.as_slice());
+        s.append(&self.coinbase.as_slice());
+        s.append(&self.state_root.as_slice());
+        s.append(&self.transactions_root.as_slice());
+        s.append(&self.receipts_root.as_slice());
+        s.append(&self.logs_bloom);
+        s.append(&self.difficulty);
+        s.append(&self.number);
+        s.append(&self.gas_limit);
+        s.append(&self.gas_used);
+        s.append(&self.timestamp);
+        s.append(&self.extra_data);
+        s.append(&self.mix_hash.as_slice());
+        s.append(&self.nonce);
+        s.append(&self.base_fee_per_gas);
+    }
+}
+
+impl Decodable for BlockHeader {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 16)?;
+        Ok(BlockHeader {
+            parent_hash: fixed(rlp, 0)?,
+            uncles_hash: fixed(rlp, 1)?,
+            coinbase: fixed(rlp, 2)?,
+            state_root: fixed(rlp, 3)?,
+            transactions_root: fixed(rlp, 4)?,
+            receipts_root: fixed(rlp, 5)?,
+            logs_bloom: rlp.val_at(6)?,
+            difficulty: rlp.val_at(7)?,
+            number: rlp.val_at(8)?,
+            gas_limit: rlp.val_at(9)?,
+            gas_used: rlp.val_at(10)?,
+            timestamp: rlp.val_at(11)?,
+            extra_data: rlp.val_at(12)?,
+            mix_hash: fixed(rlp, 13)?,
+            nonce: rlp.val_at(14)?,
+            base_fee_per_gas: rlp.val_at(15)?,
+        })
+    }
+}
+
+impl Encodable for EventLog {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.address.as_slice());
+        append_fixed_list(s, &self.topics);
+        s.append(&self.data);
+        s.append(&self.block_number);
+        s.append(&self.transaction_hash.as_slice());
+        s.append(&self.log_index);
+    }
+}
+
+impl Decodable for EventLog {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 6)?;
+        Ok(EventLog {
+            address: fixed(rlp, 0)?,
+            topics: fixed_list(rlp, 1)?,
+            data: rlp.val_at(2)?,
+            block_number: rlp.val_at(3)?,
+            transaction_hash: fixed(rlp, 4)?,
+            log_index: rlp.val_at(5)?,
+        })
+    }
+}
+
+impl Encodable for MerkleProof {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.leaf.as_slice());
+        s.append(&self.root.as_slice());
+        append_fixed_list(s, &self.siblings);
+        s.append(&self.index);
+    }
+}
+
+impl Decodable for MerkleProof {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 4)?;
+        Ok(MerkleProof { leaf: fixed(rlp, 0)?, root: fixed(rlp, 1)?, siblings: fixed_list(rlp, 2)?, index: rlp.val_at(3)? })
+    }
+}
+
+// schema versions of SampleData. an added field goes at the end and is left out
+// when empty, how typed transactions grew new fields
+impl Encodable for SampleDataAdded {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(if self.chain_id.is_some() { 5 } else { 4 });
+        s.append(&self.id);
+        s.append(&self.name);
+        s.append(&self.active);
+        s.append(&self.values);
+        if let Some(chain_id) = self.chain_id {
+            s.append(&chain_id);
+        }
+    }
+}
+
+impl Decodable for SampleDataAdded {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let chain_id = match rlp.item_count()? {
+            4 => None,
+            5 => Some(rlp.val_at(4)?),
+            _ => return Err(DecoderError::RlpIncorrectListLen),
+        };
+        Ok(SampleDataAdded { id: rlp.val_at(0)?, name: rlp.val_at(1)?, active: rlp.val_at(2)?, values: rlp.val_at(3)?, chain_id })
+    }
+}
+
+impl Encodable for SampleDataRemoved {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(3);
+        s.append(&self.id);
+        s.append(&self.name);
+        s.append(&self.values);
+    }
+}
+
+impl Decodable for SampleDataRemoved {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 3)?;
+        Ok(SampleDataRemoved { id: rlp.val_at(0)?, name: rlp.val_at(1)?, values: rlp.val_at(2)? })
+    }
+}
+
+impl Encodable for SampleDataReordered {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.values);
+        s.append(&self.active);
+        s.append(&self.name);
+        s.append(&self.id);
+    }
+}
+
+impl Decodable for SampleDataReordered {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        check_len(rlp, 4)?;
+        Ok(SampleDataReordered { values: rlp.val_at(0)?, active: rlp.val_at(1)?, name: rlp.val_at(2)?, id: rlp.val_at(3)? })
+    }
+}
@@ -0,0 +1,152 @@
+// encode and decode on 1..N threads at once. every thread works on its own copy
+// of the data, cloned on that thread before the timing starts, so anything below
+// linear scaling is contention: the global allocator or memory bandwidth
+use serde::Serialize;
+use std::hint::black_box;
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+
+use crate::baseline::time;
+use crate::{for_each_fixture, for_each_format, for_each_zero_copy_format, Fixture, FixtureVisitor, Format, FormatVisitor};
+use crate::{Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+// time one thread should run per measurement
+const RUN_TIME: Duration = Duration::from_millis(20);
+
+// 1, 2, 4 .. up to max, max itself always included
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = (0..).map(|i| 1 << i).take_while(|&n| n < max).collect();
+    counts.push(max.max(1));
+    counts
+}
+
+pub fn available_threads() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+// wall time for every thread to call f iterations times on what setup gave that
+// thread. threads start together behind a barrier so thread startup and setup
+// are not timed
+pub fn run_parallel<S, R>(threads: usize, iterations: u64, setup: impl Fn() -> S + Sync, f: impl Fn(&S) -> R + Sync) -> Duration {
+    let barrier = Barrier::new(threads + 1);
+    std::thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let state = setup();
+                barrier.wait();
+                for _ in 0..iterations {
+                    black_box(f(&state));
+                }
+            });
+        }
+        barrier.wait();
+        Instant::now()
+    })
+    .elapsed()
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ScalingRow {
+    pub fixture: String,
+    pub format: String,
+    pub operation: String,
+    pub threads: usize,
+    // calls per second over all threads
+    pub ops_per_sec: f64,
+    // ops_per_sec / (threads * single thread ops_per_sec), 1.0 is linear
+    pub efficiency: f64,
+}
+
+struct Rows<'a, T> {
+    rows: &'a mut Vec<ScalingRow>,
+    fixture: &'a str,
+    data: &'a T,
+    threads: &'a [usize],
+}
+
+impl<T> Rows<'_, T> {
+    fn measure<S, R>(&mut self, format: &str, operation: &str, setup: impl Fn() -> S + Sync, f: impl Fn(&S) -> R + Sync) {
+        let state = setup();
+        let iterations = (RUN_TIME.as_nanos() as f64 / time(|| f(&state))).ceil().max(1.0) as u64;
+        let mut single = 0.0;
+        for &threads in self.threads {
+            let elapsed = run_parallel(threads, iterations, &setup, &f);
+            let ops_per_sec = (threads as u64 * iterations) as f64 / elapsed.as_secs_f64();
+            if threads == 1 {
+                single = ops_per_sec;
+            }
+            let efficiency = if single > 0.0 { ops_per_sec / (threads as f64 * single) } else { 0.0 };
+            self.rows.push(ScalingRow {
+                fixture: self.fixture.to_string(),
+                format: format.to_string(),
+                operation: operation.to_string(),
+                threads,
+                ops_per_sec,
+                efficiency,
+            });
+        }
+    }
+}
+
+impl<T: Record + Sync> FormatVisitor for Rows<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        let data = self.data;
+        let bytes = F::encode(data);
+        self.measure(F::NAME, "encode", || data.clone(), |data| F::encode(data));
+        self.measure(F::NAME, "decode", || bytes.clone(), |bytes| F::decode::<T>(bytes).unwrap());
+    }
+}
+
+impl<T: Record + Sync> ZeroCopyVisitor for Rows<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let data = self.data;
+        let bytes = Z::encode(data);
+        self.measure(Z::NAME, "encode", || data.clone(), |data| Z::encode(data));
+        self.measure(Z::NAME, "decode", || bytes.clone(), |bytes| Z::decode::<T>(bytes).unwrap());
+    }
+}
+
+struct Fixtures<'a> {
+    rows: Vec<ScalingRow>,
+    threads: &'a [usize],
+}
+
+impl FixtureVisitor for Fixtures<'_> {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        let threads = self.threads;
+        for_each_format(&mut Rows { rows: &mut self.rows, fixture: name, data, threads });
+        for_each_zero_copy_format(&mut Rows { rows: &mut self.rows, fixture: name, data, threads });
+    }
+}
+
+// threads must start with 1, efficiency is relative to it
+pub fn scaling_rows(threads: &[usize]) -> Vec<ScalingRow> {
+    let mut fixtures = Fixtures { rows: Vec::new(), threads };
+    for_each_fixture(&mut fixtures);
+    fixtures.rows
+}
+
+// one table per fixture, one line per format and operation, a column per thread count
+pub fn to_markdown(rows: &[ScalingRow], threads: &[usize]) -> String {
+    let mut out = String::from("# Thread scaling\n\n");
+    out.push_str("Efficiency is throughput on n threads over n times the single thread throughput, 100% is linear. ");
+    out.push_str(&format!("{} threads available.\n", available_threads()));
+    let header: Vec<String> = threads.iter().map(|n| format!("{} threads", n)).collect();
+    let mut fixture = "";
+    for line in rows.chunks(threads.len()) {
+        let first = &line[0];
+        if first.fixture != fixture {
+            fixture = &first.fixture;
+            out.push_str(&format!("\n## {}\n\n", fixture));
+            out.push_str(&format!("| format | operation | 1 thread ops/s | {} |\n", header.join(" | ")));
+            out.push_str(&format!("|---|---|---:|{}\n", "---:|".repeat(threads.len())));
+        }
+        let cells: Vec<String> = line.iter().map(|r| format!("{:.0}%", r.efficiency * 100.0)).collect();
+        out.push_str(&format!("| {} | {} | {:.0} | {} |\n", first.format, first.operation, first.ops_per_sec, cells.join(" | ")));
+    }
+    out
+}
+
+pub fn to_json(rows: &[ScalingRow]) -> String {
+    serde_json::to_string_pretty(rows).unwrap()
+}
@@ -0,0 +1,82 @@
+// serialized size of every format on every fixture, raw and compressed.
+// sizes are fixed so they go in a table, not a criterion bench
+use serde::Serialize;
+
+use crate::{for_each_fixture, for_each_format, for_each_zero_copy_format, FixtureVisitor, Format, FormatVisitor};
+use crate::{Fixture, Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SizeRow {
+    pub fixture: String,
+    pub format: String,
+    pub raw: usize,
+    pub zstd: usize,
+    pub lz4: usize,
+    pub snappy: usize,
+}
+
+impl SizeRow {
+    pub fn new(fixture: &str, format: &str, bytes: &[u8]) -> Self {
+        SizeRow {
+            fixture: fixture.to_string(),
+            format: format.to_string(),
+            raw: bytes.len(),
+            zstd: zstd::bulk::compress(bytes, 3).unwrap().len(), // default level
+            lz4: lz4_flex::compress(bytes).len(),
+            snappy: snap::raw::Encoder::new().compress_vec(bytes).unwrap().len(),
+        }
+    }
+}
+
+struct Rows<'a, T> {
+    rows: &'a mut Vec<SizeRow>,
+    fixture: &'a str,
+    data: &'a T,
+}
+
+impl<T: Record> FormatVisitor for Rows<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        self.rows.push(SizeRow::new(self.fixture, F::NAME, &F::encode(self.data)));
+    }
+}
+
+impl<T: Record> ZeroCopyVisitor for Rows<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.rows.push(SizeRow::new(self.fixture, Z::NAME, &Z::encode(self.data)));
+    }
+}
+
+struct Fixtures(Vec<SizeRow>);
+
+impl FixtureVisitor for Fixtures {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        for_each_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+        for_each_zero_copy_format(&mut Rows { rows: &mut self.0, fixture: name, data });
+    }
+}
+
+pub fn size_rows() -> Vec<SizeRow> {
+    let mut fixtures = Fixtures(Vec::new());
+    for_each_fixture(&mut fixtures);
+    fixtures.0
+}
+
+// one table per fixture so formats are easy to compare
+pub fn to_markdown(rows: &[SizeRow]) -> String {
+    let mut out = String::from("# Serialized sizes\n\nBytes per encoded value. zstd is level 3, lz4 is the block format, snappy is raw.\n");
+    let mut fixture = "";
+    for row in rows {
+        if row.fixture != fixture {
+            fixture = &row.fixture;
+            out.push_str(&format!("\n## {}\n\n", fixture));
+            out.push_str("| format | raw | zstd | lz4 | snappy |\n");
+            out.push_str("|---|---:|---:|---:|---:|\n");
+        }
+        out.push_str(&format!("| {} | {} | {} | {} | {} |\n", row.format, row.raw, row.zstd, row.lz4, row.snappy));
+    }
+    out
+}
+
+pub fn to_json(rows: &[SizeRow]) -> String {
+    serde_json::to_string_pretty(rows).unwrap()
+}
@@ -0,0 +1,109 @@
+// length delimited record streams: every record is its LEB128 length then the
+// encoded bytes (like protobuf writeDelimitedTo), so any format can be streamed
+// to a Write and read back one record at a time
+use std::io::{self, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+
+use crate::zero_copy::Codec;
+use crate::Record;
+
+// longest record a RecordReader accepts unless with_max_len says otherwise
+pub const MAX_RECORD_LEN: usize = 16 * 1024 * 1024;
+
+fn write_len<W: Write>(writer: &mut W, mut len: usize) -> io::Result<()> {
+    let mut buf = [0u8; 10];
+    let mut i = 0;
+    while len >= 0x80 {
+        buf[i] = len as u8 | 0x80;
+        len >>= 7;
+        i += 1;
+    }
+    buf[i] = len as u8;
+    writer.write_all(&buf[..=i])
+}
+
+// None on a clean end of stream, an error when it ends inside the length
+fn read_len<R: Read>(reader: &mut R) -> io::Result<Option<usize>> {
+    let mut len = 0usize;
+    for i in 0..10 {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            if i == 0 {
+                return Ok(None);
+            }
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        len |= ((byte[0] & 0x7f) as usize) << (7 * i);
+        if byte[0] < 0x80 {
+            return Ok(Some(len));
+        }
+    }
+    Err(io::Error::new(ErrorKind::InvalidData, "record length too long"))
+}
+
+pub fn write_record<C: Codec, T: Record, W: Write>(writer: &mut W, record: &T) -> io::Result<()> {
+    let bytes = C::encode(record);
+    write_len(writer, bytes.len())?;
+    writer.write_all(&bytes)
+}
+
+pub fn write_records<C: Codec, T: Record, W: Write>(writer: &mut W, records: &[T]) -> io::Result<()> {
+    for record in records {
+        write_record::<C, T, W>(writer, record)?;
+    }
+    Ok(())
+}
+
+// the whole batch in one buffer
+pub fn encode_batch<C: Codec, T: Record>(records: &[T]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_records::<C, T, _>(&mut out, records).unwrap();
+    out
+}
+
+// decodes one record per next(), only one record is in memory at a time.
+// wrap files and sockets in a BufReader, the length is read a byte at a time
+pub struct RecordReader<C, T, R> {
+    reader: R,
+    buf: Vec<u8>,
+    max_len: usize,
+    _record: PhantomData<(C, T)>,
+}
+
+impl<C: Codec, T: Record, R: Read> RecordReader<C, T, R> {
+    pub fn new(reader: R) -> Self {
+        RecordReader { reader, buf: Vec::new(), max_len: MAX_RECORD_LEN, _record: PhantomData }
+    }
+
+    // longest record to read, a longer length prefix is InvalidData
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    fn read_next(&mut self) -> io::Result<Option<T>> {
+        let len = match read_len(&mut self.reader)? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        // checked before the buffer grows, a corrupt prefix is not an allocation
+        if len > self.max_len {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("record of {} bytes, limit is {}", len, self.max_len)));
+        }
+        self.buf.resize(len, 0);
+        self.reader.read_exact(&mut self.buf)?;
+        C::decode(&self.buf).map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl<C: Codec, T: Record, R: Read> Iterator for RecordReader<C, T, R> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_next().transpose()
+    }
+}
+
+pub fn read_records<C: Codec, T: Record, R: Read>(reader: R) -> io::Result<Vec<T>> {
+    RecordReader::<C, T, R>::new(reader).collect()
+}
@@ -0,0 +1,151 @@
+// formats that can read a field straight out of the encoded bytes (rkyv,
+// cap'n proto, flatbuffers). every record has a key field, what our indexer reads
+use rkyv::util::AlignedVec;
+use std::marker::PhantomData;
+
+use crate::capnp_codec::CapnpRecord;
+use crate::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use crate::flatbuffers_codec::FlatbufferRecord;
+use crate::{Format, Payload, SampleData};
+
+pub trait RkyvRecord: Sized {
+    fn write_rkyv(&self) -> AlignedVec;
+    fn read_rkyv(bytes: &[u8]) -> Result<Self, String>;
+    fn rkyv_key(bytes: &[u8]) -> Result<u64, String>;
+}
+
+// payload with a key and every zero copy encoding
+pub trait Record: Payload + RkyvRecord + CapnpRecord + FlatbufferRecord {
+    fn key(&self) -> u64;
+}
+
+// also used by the schema versions in evolution.rs
+macro_rules! record {
+    ($ty:ty, $key:ident) => {
+        impl $crate::zero_copy::RkyvRecord for $ty {
+            fn write_rkyv(&self) -> rkyv::util::AlignedVec {
+                rkyv::to_bytes::<rkyv::rancor::Error>(self).unwrap()
+            }
+            fn read_rkyv(bytes: &[u8]) -> Result<Self, String> {
+                rkyv::from_bytes::<$ty, rkyv::rancor::Error>(bytes).map_err(|e| e.to_string())
+            }
+            // access validate the archive, then read the field in place
+            fn rkyv_key(bytes: &[u8]) -> Result<u64, String> {
+                let archived = rkyv::access::<rkyv::Archived<$ty>, rkyv::rancor::Error>(bytes).map_err(|e| e.to_string())?;
+                Ok(archived.$key.to_native() as u64)
+            }
+        }
+
+        impl $crate::zero_copy::Record for $ty {
+            fn key(&self) -> u64 {
+                self.$key as u64
+            }
+        }
+    };
+}
+
+pub(crate) use record;
+
+record!(SampleData, id);
+record!(SignedTransaction, nonce);
+record!(BlockHeader, number);
+record!(EventLog, block_number);
+record!(MerkleProof, index);
+
+// bytes are kept in an AlignedVec, rkyv and capnp read aligned data in place
+pub trait ZeroCopyFormat {
+    const NAME: &'static str;
+    fn encode<T: Record>(value: &T) -> AlignedVec;
+    // read the key without materialising the record
+    fn access<T: Record>(bytes: &[u8]) -> Result<u64, String>;
+    // full materialisation into the owned type
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String>;
+}
+
+pub(crate) fn aligned(bytes: &[u8]) -> AlignedVec {
+    let mut vec = AlignedVec::with_capacity(bytes.len());
+    vec.extend_from_slice(bytes);
+    vec
+}
+
+pub struct Rkyv;
+impl ZeroCopyFormat for Rkyv {
+    const NAME: &'static str = "rkyv";
+    fn encode<T: Record>(value: &T) -> AlignedVec {
+        value.write_rkyv()
+    }
+    fn access<T: Record>(bytes: &[u8]) -> Result<u64, String> {
+        T::rkyv_key(bytes)
+    }
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String> {
+        T::read_rkyv(bytes)
+    }
+}
+
+pub struct Capnp;
+impl ZeroCopyFormat for Capnp {
+    const NAME: &'static str = "capnp";
+    fn encode<T: Record>(value: &T) -> AlignedVec {
+        aligned(&value.write_capnp())
+    }
+    fn access<T: Record>(bytes: &[u8]) -> Result<u64, String> {
+        T::capnp_key(bytes)
+    }
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String> {
+        T::read_capnp(bytes)
+    }
+}
+
+pub struct Flatbuffers;
+impl ZeroCopyFormat for Flatbuffers {
+    const NAME: &'static str = "flatbuffers";
+    fn encode<T: Record>(value: &T) -> AlignedVec {
+        aligned(&value.write_flatbuffer())
+    }
+    fn access<T: Record>(bytes: &[u8]) -> Result<u64, String> {
+        T::flatbuffer_key(bytes)
+    }
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String> {
+        T::read_flatbuffer(bytes)
+    }
+}
+
+pub trait ZeroCopyVisitor {
+    fn visit<Z: ZeroCopyFormat>(&mut self);
+}
+
+pub fn for_each_zero_copy_format<V: ZeroCopyVisitor>(visitor: &mut V) {
+    visitor.visit::<Rkyv>();
+    visitor.visit::<Capnp>();
+    visitor.visit::<Flatbuffers>();
+}
+
+// both format traits behind one, for the tools and streams that run every format
+pub trait Codec {
+    const NAME: &'static str;
+    fn encode<T: Record>(value: &T) -> Vec<u8>;
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String>;
+}
+
+pub struct Plain<F>(PhantomData<F>);
+impl<F: Format> Codec for Plain<F> {
+    const NAME: &'static str = F::NAME;
+    fn encode<T: Record>(value: &T) -> Vec<u8> {
+        F::encode(value)
+    }
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String> {
+        F::decode(bytes)
+    }
+}
+
+pub struct ZeroCopy<Z>(PhantomData<Z>);
+impl<Z: ZeroCopyFormat> Codec for ZeroCopy<Z> {
+    const NAME: &'static str = Z::NAME;
+    fn encode<T: Record>(value: &T) -> Vec<u8> {
+        Z::encode(value).to_vec()
+    }
+    // decode from an aligned copy, like a record read back from disk
+    fn decode<T: Record>(bytes: &[u8]) -> Result<T, String> {
+        Z::decode(&aligned(bytes))
+    }
+}
@@ -0,0 +1,43 @@
+// the counting allocator is installed for this test binary only, one test so
+// nothing else allocates while it measures
+use benchmark::allocations::{measure, CountingAllocator};
+use benchmark::borrowed::{BorrowedFormat, SampleDataRef};
+use benchmark::{sample_data_large, Format, SampleData, ZeroCopyFormat};
+use benchmark::formats::Bincode;
+use benchmark::zero_copy::Rkyv;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn counts_allocations() {
+    let (buffer, stats) = measure(|| vec![0u8; 4096]);
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.bytes, 4096);
+    assert_eq!(stats.peak, 4096);
+
+    // freed memory is not peak, it only counts what was live at once
+    let (_, stats) = measure(|| {
+        drop(vec![0u8; 1000]);
+        drop(vec![0u8; 1000]);
+    });
+    assert_eq!((stats.allocations, stats.bytes, stats.peak), (2, 2000, 1000));
+    drop(buffer);
+
+    let data = sample_data_large();
+    let (bytes, stats) = measure(|| Bincode::encode(&data));
+    assert!(stats.allocations >= 1 && stats.bytes >= bytes.len());
+
+    // reading a field in place does not touch the heap
+    let bytes = Rkyv::encode(&data);
+    let (key, stats) = measure(|| Rkyv::access::<SampleData>(&bytes).unwrap());
+    assert_eq!(key, data.id as u64);
+    assert_eq!(stats.allocations, 0);
+
+    // borrowed decode points into the bytes, the owned one copies name and values
+    let bytes = Bincode::encode(&data);
+    let (_, owned) = measure(|| Bincode::decode::<SampleData>(&bytes).unwrap());
+    let (_, borrowed) = measure(|| Bincode::decode_borrowed::<SampleDataRef>(&bytes).unwrap());
+    assert_eq!(owned.allocations, 2);
+    assert_eq!(borrowed.allocations, 0);
+}
@@ -0,0 +1,59 @@
+use benchmark::baseline::{compare, machine, time, to_markdown, unmatched, Measurement, Run};
+
+fn run(revision: &str, results: &[(&str, f64)]) -> Run {
+    let results = results
+        .iter()
+        .map(|(format, ns)| Measurement { fixture: "small".to_string(), format: format.to_string(), operation: "encode".to_string(), ns: *ns })
+        .collect();
+    Run { revision: revision.to_string(), machine: machine(), timestamp: 0, results }
+}
+
+#[test]
+fn flags_rows_over_threshold() {
+    let baseline = run("aaaaaaa", &[("bincode", 100.0), ("borsh", 100.0), ("rlp", 100.0)]);
+    let current = run("bbbbbbb", &[("bincode", 105.0), ("borsh", 150.0), ("rlp", 50.0), ("ssz", 10.0)]);
+    let comparisons = compare(&baseline, &current, 0.10);
+
+    // ssz is not in the baseline so it has nothing to compare to
+    assert_eq!(comparisons.len(), 3);
+    let regressed: Vec<&str> = comparisons.iter().filter(|c| c.regressed).map(|c| c.format.as_str()).collect();
+    assert_eq!(regressed, vec!["borsh"]);
+    assert!((comparisons[1].change - 0.5).abs() < 1e-9);
+
+    let report = to_markdown(&baseline, &current, &comparisons, 0.10);
+    assert!(report.contains("## 1 regressions"));
+    assert!(report.contains("| small | borsh | encode | 100.0 | 150.0 | +50.0% **regressed** |"));
+}
+
+#[test]
+fn reports_missing_and_new_rows() {
+    let baseline = run("aaaaaaa", &[("bincode", 100.0), ("borsh", 100.0)]);
+    let current = run("bbbbbbb", &[("bincode", 100.0), ("ssz", 10.0)]);
+    let missing: Vec<&str> = unmatched(&baseline, &current).iter().map(|m| m.format.as_str()).collect();
+    let new: Vec<&str> = unmatched(&current, &baseline).iter().map(|m| m.format.as_str()).collect();
+    assert_eq!(missing, vec!["borsh"]);
+    assert_eq!(new, vec!["ssz"]);
+
+    let comparisons = compare(&baseline, &current, 0.10);
+    let report = to_markdown(&baseline, &current, &comparisons, 0.10);
+    assert!(report.contains("## 1 missing rows"));
+    assert!(report.contains("| small | borsh | encode | 100.0 |"));
+    assert!(report.contains("## 1 new rows"));
+    assert!(report.contains("| small | ssz | encode | 10.0 |"));
+
+    assert!(unmatched(&baseline, &baseline).is_empty());
+    assert!(!to_markdown(&baseline, &baseline, &compare(&baseline, &baseline, 0.10), 0.10).contains("missing"));
+}
+
+#[test]
+fn run_survives_json() {
+    let original = run("aaaaaaa-dirty", &[("bincode", 12.5)]);
+    assert_eq!(Run::from_json(&original.to_json()).unwrap(), original);
+    assert!(Run::from_json("{}").is_err());
+}
+
+#[test]
+fn times_a_call() {
+    let ns = time(|| (0..1000u64).map(std::hint::black_box).sum::<u64>());
+    assert!(ns > 0.0);
+}
@@ -0,0 +1,53 @@
+// the borrowed type reads what the owned one writes, and borrows where the
+// format lets it
+use benchmark::borrowed::{for_each_borrowed_format, BorrowedFormat, BorrowedVisitor, SampleDataRef};
+use benchmark::{sample_data, sample_data_large, SampleData};
+
+struct Borrows<'a> {
+    data: &'a SampleData,
+    found: Vec<(&'static str, (bool, bool))>,
+}
+
+impl BorrowedVisitor for Borrows<'_> {
+    fn visit<F: BorrowedFormat>(&mut self) {
+        let bytes = F::encode(self.data);
+        let decoded: SampleDataRef = F::decode_borrowed(&bytes).unwrap();
+        assert!(decoded.to_owned_data() == *self.data, "{}", F::NAME);
+        self.found.push((F::NAME, decoded.borrowed()));
+    }
+}
+
+fn borrows(data: &SampleData) -> Vec<(&'static str, (bool, bool))> {
+    let mut borrows = Borrows { data, found: Vec::new() };
+    for_each_borrowed_format(&mut borrows);
+    borrows.found
+}
+
+#[test]
+fn binary_formats_borrow_both_fields() {
+    let expected = vec![
+        ("bincode", (true, true)),
+        ("postcard", (true, true)),
+        // Vec<u8> is an array of numbers in rmp and json
+        ("rmp", (true, false)),
+        ("serde_json", (true, false)),
+        ("bcs", (true, true)),
+    ];
+    assert_eq!(borrows(&sample_data()), expected);
+}
+
+#[test]
+fn escaped_json_string_is_copied() {
+    let found = borrows(&sample_data_large());
+    assert_eq!(found[3], ("serde_json", (false, false)));
+    assert_eq!(found[0], ("bincode", (true, true)));
+}
+
+#[test]
+fn writes_what_the_owned_type_writes() {
+    let data = sample_data_large();
+    let borrowed = SampleDataRef::from(&data);
+    assert_eq!(bincode::serialize(&borrowed).unwrap(), bincode::serialize(&data).unwrap());
+    assert_eq!(rmp_serde::to_vec(&borrowed).unwrap(), rmp_serde::to_vec(&data).unwrap());
+    assert_eq!(serde_json::to_vec(&borrowed).unwrap(), serde_json::to_vec(&data).unwrap());
+}
@@ -0,0 +1,83 @@
+// the formats we would hash or sign have to stay canonical, and the checker has
+// to keep catching the ones that are not
+use benchmark::canonical::{verdicts, Check};
+use benchmark::{for_each_format, for_each_zero_copy_format, Format, FormatVisitor, ZeroCopyFormat, ZeroCopyVisitor};
+
+struct Names(Vec<&'static str>);
+
+impl FormatVisitor for Names {
+    fn visit<F: Format>(&mut self) {
+        self.0.push(F::NAME);
+    }
+}
+
+impl ZeroCopyVisitor for Names {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.0.push(Z::NAME);
+    }
+}
+
+#[test]
+fn every_format_is_checked() {
+    let mut names = Names(Vec::new());
+    for_each_format(&mut names);
+    for_each_zero_copy_format(&mut names);
+    let checked: Vec<_> = verdicts().into_iter().map(|v| v.format).collect();
+    assert_eq!(checked, names.0);
+}
+
+#[test]
+fn consensus_formats_are_canonical() {
+    let verdicts = verdicts();
+    for format in ["bcs", "rlp", "ssz", "scale"] {
+        let verdict = verdicts.iter().find(|v| v.format == format).unwrap();
+        assert!(verdict.canonical, "{:#?}", verdict);
+    }
+}
+
+#[test]
+fn non_canonical_inputs_are_caught() {
+    let verdicts = verdicts();
+    for format in ["serde_json", "protobuf", "rmp", "flatbuffers"] {
+        let verdict = verdicts.iter().find(|v| v.format == format).unwrap();
+        assert!(!verdict.canonical, "{}", format);
+    }
+    let protobuf = verdicts.iter().find(|v| v.format == "protobuf").unwrap();
+    let varint = protobuf.checks.iter().find(|c| c.name == "overlong varint").unwrap();
+    assert!(matches!(varint.check, Check::Fail(_)));
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    for verdict in verdicts() {
+        let trailing = verdict.checks.iter().find(|c| c.name == "trailing byte").unwrap();
+        match verdict.format.as_str() {
+            "flatbuffers" => assert!(matches!(trailing.check, Check::Fail(_))),
+            _ => assert_eq!(trailing.check, Check::Pass, "{}", verdict.format),
+        }
+    }
+}
+
+#[test]
+fn rkyv_string_padding_is_not_checked() {
+    let verdicts = verdicts();
+    let rkyv = verdicts.iter().find(|v| v.format == "rkyv").unwrap();
+    let padding = rkyv.checks.iter().find(|c| c.name == "string padding").unwrap();
+    assert!(matches!(padding.check, Check::Fail(_)));
+}
+
+// map order has to fail every run for the formats that write a HashMap as it iterates
+#[test]
+fn map_order_is_caught() {
+    let verdicts = verdicts();
+    let map_order = |format: &str| {
+        let verdict = verdicts.iter().find(|v| v.format == format).unwrap();
+        verdict.checks.iter().find(|c| c.name == "map order").unwrap().check.clone()
+    };
+    for format in ["bincode", "serde_json", "rmp", "postcard", "cbor"] {
+        assert!(matches!(map_order(format), Check::Fail(_)), "{}", format);
+    }
+    // both sort the keys
+    assert_eq!(map_order("bcs"), Check::Pass);
+    assert_eq!(map_order("borsh"), Check::Pass);
+}
@@ -0,0 +1,34 @@
+// formats with a schema keep reading records across every change when the
+// schema is evolved by their rules, the matrix must keep showing that
+use benchmark::evolution::{evolution_matrix, Outcome};
+
+#[test]
+fn schema_formats_stay_compatible() {
+    let cells = evolution_matrix();
+    for format in ["protobuf", "capnp", "flatbuffers"] {
+        let row: Vec<_> = cells.iter().filter(|cell| cell.format == format).collect();
+        assert_eq!(row.len(), 3, "{}", format);
+        for cell in row {
+            assert_eq!(cell.old_reads_new, Outcome::Ok, "{} {}", format, cell.change);
+            assert_eq!(cell.new_reads_old, Outcome::Ok, "{} {}", format, cell.change);
+        }
+    }
+}
+
+#[test]
+fn every_format_is_in_the_matrix() {
+    let cells = evolution_matrix();
+    assert_eq!(cells.len(), 16 * 3);
+}
+
+// avro resolves the writer schema from the registry against the reader's own.
+// a removed field only reads back when the reader has a default for it
+#[test]
+fn avro_resolves_added_and_reordered_fields() {
+    let cells = evolution_matrix();
+    for change in ["added field", "reordered fields"] {
+        let cell = cells.iter().find(|cell| cell.format == "avro" && cell.change == change).unwrap();
+        assert_eq!(cell.old_reads_new, Outcome::Ok, "{}", change);
+        assert_eq!(cell.new_reads_old, Outcome::Ok, "{}", change);
+    }
+}
@@ -0,0 +1,48 @@
+// the hand written prost attributes and Message impls must match proto/payloads.proto:
+// prost-build and rust-protobuf output from the schema give the same bytes and
+// read each other
+use benchmark::fixtures::{signed_transaction, SignedTransaction};
+use benchmark::proto::Proto;
+use benchmark::{for_each_fixture, Fixture, FixtureVisitor};
+use prost::Message as _;
+use protobuf::Message as _;
+
+struct Check(Vec<String>);
+
+impl FixtureVisitor for Check {
+    fn visit<T: Fixture>(&mut self, name: &str, value: &T) {
+        let hand = value.encode_to_vec();
+        let prost = value.to_prost().encode_to_vec();
+        let rust_protobuf = value.to_rust_protobuf().write_to_bytes().unwrap();
+        if hand != prost {
+            self.0.push(format!("{}: hand written prost differs from prost-build", name));
+        }
+        if prost != rust_protobuf {
+            self.0.push(format!("{}: prost-build differs from rust-protobuf", name));
+        }
+        if T::RustProtobuf::parse_from_bytes(&prost).ok() != Some(value.to_rust_protobuf()) {
+            self.0.push(format!("{}: rust-protobuf can not read prost-build bytes", name));
+        }
+        if T::Prost::decode(&rust_protobuf[..]).ok() != Some(value.to_prost()) {
+            self.0.push(format!("{}: prost-build can not read rust-protobuf bytes", name));
+        }
+    }
+}
+
+#[test]
+fn generated_types_match_payloads() {
+    let mut check = Check(Vec::new());
+    for_each_fixture(&mut check);
+    assert!(check.0.is_empty(), "{:#?}", check.0);
+}
+
+// hashes and addresses are plain bytes on the wire, the hand written Message
+// only takes them at their fixed length
+#[test]
+fn wrong_length_hash_is_rejected() {
+    let transaction = signed_transaction(68);
+    let mut generated = transaction.to_prost();
+    assert_eq!(SignedTransaction::decode(&generated.encode_to_vec()[..]).unwrap(), transaction);
+    generated.r.pop();
+    assert!(SignedTransaction::decode(&generated.encode_to_vec()[..]).is_err());
+}
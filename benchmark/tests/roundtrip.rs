@@ -0,0 +1,201 @@
+// every format must give back exactly what went in, and encode the same value
+// to the same bytes every time. runs all fixtures, edge cases and random values
+// through every format the benches use
+use benchmark::evolution::{SampleDataAdded, SampleDataRemoved, SampleDataReordered, Version};
+use benchmark::fixtures::{BlockHeader, EventLog, MerkleProof, SignedTransaction};
+use benchmark::{
+    for_each_fixture, for_each_format, for_each_zero_copy_format, Fixture, FixtureVisitor, Format, FormatVisitor, Record,
+    SampleData, ZeroCopyFormat, ZeroCopyVisitor,
+};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+// collects one message per broken format instead of stopping at the first
+struct Check<'a, T> {
+    value: &'a T,
+    errors: Vec<String>,
+}
+
+impl<T: Record> Check<'_, T> {
+    fn bytes(&mut self, format: &str, bytes: &[u8], again: &[u8], decoded: Result<T, String>, encode: impl Fn(&T) -> Vec<u8>) {
+        if bytes != again {
+            self.errors.push(format!("{}: encoding is not deterministic", format));
+        }
+        match decoded {
+            Err(e) => self.errors.push(format!("{}: decode failed: {}", format, e)),
+            Ok(decoded) if decoded != *self.value => self.errors.push(format!("{}: lossy, got {:?}", format, decoded)),
+            // decoded value is equal, so encoding it again has to give the same bytes
+            Ok(decoded) if encode(&decoded) != bytes => self.errors.push(format!("{}: re-encoding changed the bytes", format)),
+            Ok(_) => {}
+        }
+    }
+}
+
+impl<T: Record> FormatVisitor for Check<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        let bytes = F::encode(self.value);
+        let again = F::encode(self.value);
+        self.bytes(F::NAME, &bytes, &again, F::decode(&bytes), F::encode);
+    }
+}
+
+impl<T: Record> ZeroCopyVisitor for Check<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        let bytes = Z::encode(self.value);
+        let again = Z::encode(self.value);
+        self.bytes(Z::NAME, &bytes, &again, Z::decode(&bytes), |v| Z::encode(v).to_vec());
+        match Z::access::<T>(&bytes) {
+            Ok(key) if key == self.value.key() => {}
+            Ok(key) => self.errors.push(format!("{}: access read key {} not {}", Z::NAME, key, self.value.key())),
+            Err(e) => self.errors.push(format!("{}: access failed: {}", Z::NAME, e)),
+        }
+    }
+}
+
+fn check<T: Record>(value: &T) -> Vec<String> {
+    let mut check = Check { value, errors: Vec::new() };
+    for_each_format(&mut check);
+    for_each_zero_copy_format(&mut check);
+    check.errors
+}
+
+struct Fixtures(Vec<String>);
+
+impl FixtureVisitor for Fixtures {
+    fn visit<T: Fixture>(&mut self, name: &str, value: &T) {
+        self.0.extend(check(value).into_iter().map(|e| format!("{}: {}", name, e)));
+    }
+}
+
+#[test]
+fn fixtures_round_trip() {
+    let mut fixtures = Fixtures(Vec::new());
+    for_each_fixture(&mut fixtures);
+    assert!(fixtures.0.is_empty(), "{:#?}", fixtures.0);
+}
+
+#[test]
+fn sample_data_edge_cases() {
+    let cases = [
+        SampleData::default(),
+        SampleData { id: u32::MAX, name: String::new(), active: false, values: Vec::new() },
+        // bytes that are never valid utf8 on their own, a string format would mangle them
+        SampleData { id: 0, name: String::new(), active: true, values: (0x80..=0xff).collect() },
+        SampleData { id: 1, name: "\0nul\u{7f}\u{80}é€😀\u{10ffff}".to_string(), active: true, values: vec![0xc0, 0xc1, 0xf5, 0xff] },
+        SampleData { id: u32::MAX, name: "x".repeat(1 << 16), active: true, values: vec![0xff; 1 << 20] },
+    ];
+    for case in &cases {
+        let errors = check(case);
+        assert!(errors.is_empty(), "id {} name len {}: {:#?}", case.id, case.name.len(), errors);
+    }
+}
+
+// the v2 schemas of the evolution matrix have to round trip on their own
+#[test]
+fn schema_versions_round_trip() {
+    let mut errors = check(&SampleDataAdded::sample());
+    errors.extend(check(&SampleDataAdded { chain_id: None, ..SampleDataAdded::sample() }));
+    errors.extend(check(&SampleDataRemoved::sample()));
+    errors.extend(check(&SampleDataReordered::sample()));
+    assert!(errors.is_empty(), "{:#?}", errors);
+}
+
+fn bytes(max: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..max)
+}
+
+fn sample_data() -> impl Strategy<Value = SampleData> {
+    (any::<u32>(), any::<String>(), any::<bool>(), bytes(4096))
+        .prop_map(|(id, name, active, values)| SampleData { id, name, active, values })
+}
+
+fn signed_transaction() -> impl Strategy<Value = SignedTransaction> {
+    (any::<[u64; 4]>(), any::<[u8; 20]>(), any::<[[u8; 32]; 3]>(), bytes(1024)).prop_map(
+        |([nonce, gas_price, gas_limit, v], to, [value, r, s], data)| SignedTransaction {
+            nonce,
+            gas_price,
+            gas_limit,
+            to,
+            value,
+            data,
+            v,
+            r,
+            s,
+        },
+    )
+}
+
+fn block_header() -> impl Strategy<Value = BlockHeader> {
+    (any::<[u64; 7]>(), any::<[[u8; 32]; 6]>(), any::<[u8; 20]>(), bytes(512), bytes(64)).prop_map(
+        |(n, [parent_hash, uncles_hash, state_root, transactions_root, receipts_root, mix_hash], coinbase, logs_bloom, extra_data)| {
+            BlockHeader {
+                parent_hash,
+                uncles_hash,
+                coinbase,
+                state_root,
+                transactions_root,
+                receipts_root,
+                logs_bloom,
+                difficulty: n[0],
+                number: n[1],
+                gas_limit: n[2],
+                gas_used: n[3],
+                timestamp: n[4],
+                extra_data,
+                mix_hash,
+                nonce: n[5],
+                base_fee_per_gas: n[6],
+            }
+        },
+    )
+}
+
+fn event_log() -> impl Strategy<Value = EventLog> {
+    (any::<[u8; 20]>(), vec(any::<[u8; 32]>(), 0..6), bytes(1024), any::<u64>(), any::<[u8; 32]>(), any::<u32>()).prop_map(
+        |(address, topics, data, block_number, transaction_hash, log_index)| EventLog {
+            address,
+            topics,
+            data,
+            block_number,
+            transaction_hash,
+            log_index,
+        },
+    )
+}
+
+fn merkle_proof() -> impl Strategy<Value = MerkleProof> {
+    (any::<[[u8; 32]; 2]>(), vec(any::<[u8; 32]>(), 0..40), any::<u64>())
+        .prop_map(|([leaf, root], siblings, index)| MerkleProof { leaf, root, siblings, index })
+}
+
+proptest! {
+    #[test]
+    fn sample_data_round_trips(value in sample_data()) {
+        let errors = check(&value);
+        prop_assert!(errors.is_empty(), "{:#?}", errors);
+    }
+
+    #[test]
+    fn signed_transaction_round_trips(value in signed_transaction()) {
+        let errors = check(&value);
+        prop_assert!(errors.is_empty(), "{:#?}", errors);
+    }
+
+    #[test]
+    fn block_header_round_trips(value in block_header()) {
+        let errors = check(&value);
+        prop_assert!(errors.is_empty(), "{:#?}", errors);
+    }
+
+    #[test]
+    fn event_log_round_trips(value in event_log()) {
+        let errors = check(&value);
+        prop_assert!(errors.is_empty(), "{:#?}", errors);
+    }
+
+    #[test]
+    fn merkle_proof_round_trips(value in merkle_proof()) {
+        let errors = check(&value);
+        prop_assert!(errors.is_empty(), "{:#?}", errors);
+    }
+}
@@ -0,0 +1,42 @@
+use benchmark::scaling::{run_parallel, thread_counts, to_markdown, ScalingRow};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[test]
+fn thread_counts_double_up_to_max() {
+    assert_eq!(thread_counts(1), vec![1]);
+    assert_eq!(thread_counts(4), vec![1, 2, 4]);
+    assert_eq!(thread_counts(6), vec![1, 2, 4, 6]);
+}
+
+#[test]
+fn every_thread_runs_every_iteration() {
+    let calls = AtomicU64::new(0);
+    run_parallel(3, 50, || (), |_| calls.fetch_add(1, Ordering::Relaxed));
+    assert_eq!(calls.load(Ordering::Relaxed), 150);
+}
+
+#[test]
+fn every_thread_has_its_own_copy() {
+    let data = vec![1u8; 64];
+    let copies = Mutex::new(HashSet::new());
+    run_parallel(4, 10, || data.clone(), |copy| copies.lock().unwrap().insert(copy.as_ptr() as usize));
+    let copies = copies.into_inner().unwrap();
+    assert_eq!(copies.len(), 4);
+    assert!(!copies.contains(&(data.as_ptr() as usize)));
+}
+
+#[test]
+fn one_line_per_format_and_operation() {
+    let row = |threads, efficiency| ScalingRow {
+        fixture: "sample/small".to_string(),
+        format: "bincode".to_string(),
+        operation: "encode".to_string(),
+        threads,
+        ops_per_sec: 1000.0 * threads as f64 * efficiency,
+        efficiency,
+    };
+    let markdown = to_markdown(&[row(1, 1.0), row(2, 0.75)], &[1, 2]);
+    assert!(markdown.contains("| bincode | encode | 1000 | 100% | 75% |"));
+}
@@ -0,0 +1,107 @@
+// the size report has one row per format and fixture, and its compressed sizes
+// are the sizes of those formats' encoded bytes compressed, nothing else
+use benchmark::fixtures::merkle_proof;
+use benchmark::formats::{Avro, Bincode, Cbor, Postcard, Rmp};
+use benchmark::size_report::{size_rows, to_json, to_markdown};
+use benchmark::{for_each_fixture, for_each_format, for_each_zero_copy_format};
+use benchmark::{Fixture, FixtureVisitor, Format, FormatVisitor, Record, ZeroCopyFormat, ZeroCopyVisitor};
+
+// (fixture, format, encoded bytes) for every pair, in report order
+struct Encoded<'a, T> {
+    out: &'a mut Vec<(String, String, Vec<u8>)>,
+    fixture: &'a str,
+    data: &'a T,
+}
+
+impl<T: Record> FormatVisitor for Encoded<'_, T> {
+    fn visit<F: Format>(&mut self) {
+        self.out.push((self.fixture.to_string(), F::NAME.to_string(), F::encode(self.data)));
+    }
+}
+
+impl<T: Record> ZeroCopyVisitor for Encoded<'_, T> {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.out.push((self.fixture.to_string(), Z::NAME.to_string(), Z::encode(self.data).to_vec()));
+    }
+}
+
+struct Fixtures(Vec<(String, String, Vec<u8>)>);
+
+impl FixtureVisitor for Fixtures {
+    fn visit<T: Fixture>(&mut self, name: &str, data: &T) {
+        for_each_format(&mut Encoded { out: &mut self.0, fixture: name, data });
+        for_each_zero_copy_format(&mut Encoded { out: &mut self.0, fixture: name, data });
+    }
+}
+
+fn encoded() -> Vec<(String, String, Vec<u8>)> {
+    let mut fixtures = Fixtures(Vec::new());
+    for_each_fixture(&mut fixtures);
+    fixtures.0
+}
+
+#[test]
+fn one_row_per_format_and_fixture() {
+    let rows = size_rows();
+    let encoded = encoded();
+    assert_eq!(rows.len(), encoded.len());
+    for (row, (fixture, format, _)) in rows.iter().zip(&encoded) {
+        assert_eq!((&row.fixture, &row.format), (fixture, format));
+    }
+    let mut pairs: Vec<_> = rows.iter().map(|row| (&row.fixture, &row.format)).collect();
+    pairs.sort();
+    pairs.dedup();
+    assert_eq!(pairs.len(), rows.len(), "a format and fixture pair is reported twice");
+}
+
+#[test]
+fn compressed_sizes_are_of_the_encoded_bytes() {
+    for (row, (fixture, format, bytes)) in size_rows().iter().zip(encoded()) {
+        let at = format!("{} {}", fixture, format);
+        assert_eq!(row.raw, bytes.len(), "{}", at);
+
+        let zstd = zstd::bulk::compress(&bytes, 3).unwrap();
+        assert_eq!(zstd::bulk::decompress(&zstd, bytes.len()).unwrap(), bytes, "{}", at);
+        assert_eq!(row.zstd, zstd.len(), "{}", at);
+
+        let lz4 = lz4_flex::compress(&bytes);
+        assert_eq!(lz4_flex::decompress(&lz4, bytes.len()).unwrap(), bytes, "{}", at);
+        assert_eq!(row.lz4, lz4.len(), "{}", at);
+
+        let snappy = snap::raw::Encoder::new().compress_vec(&bytes).unwrap();
+        assert_eq!(snap::raw::Decoder::new().decompress_vec(&snappy).unwrap(), bytes, "{}", at);
+        assert_eq!(row.snappy, snappy.len(), "{}", at);
+    }
+}
+
+#[test]
+fn report_has_every_row() {
+    let rows = size_rows();
+    let markdown = to_markdown(&rows);
+    for row in &rows {
+        assert!(markdown.contains(&format!("\n## {}\n", row.fixture)), "{}", row.fixture);
+    }
+    assert_eq!(markdown.lines().filter(|line| line.starts_with("| ") && !line.starts_with("| format")).count(), rows.len());
+    let json: Vec<serde_json::Value> = serde_json::from_str(&to_json(&rows)).unwrap();
+    assert_eq!(json.len(), rows.len());
+}
+
+// a hash is one byte string in every serde format, the same bytes whether or
+// not they go through Format
+#[test]
+fn hashes_are_written_as_bytes() {
+    let proof = merkle_proof(10);
+    let leaf = proof.leaf.as_slice();
+    let contains = |bytes: Vec<u8>, part: &[u8]| bytes.windows(part.len()).any(|window| window == part);
+    assert!(contains(Cbor::encode(&proof), &[&[0x58, 0x20], leaf].concat()));
+    assert!(contains(Rmp::encode(&proof), &[&[0xc4, 0x20], leaf].concat()));
+    // avro fixed has no length, the leaf comes right after the single object header
+    assert_eq!(&Avro::encode(&proof)[10..42], leaf);
+    assert!(Bincode::encode(&proof).starts_with(&[&32u64.to_le_bytes()[..], leaf].concat()));
+    assert!(Postcard::encode(&proof).starts_with(&[&[0x20], leaf].concat()));
+
+    assert_eq!(Rmp::encode(&proof), rmp_serde::to_vec(&proof).unwrap());
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&proof, &mut cbor).unwrap();
+    assert_eq!(Cbor::encode(&proof), cbor);
+}
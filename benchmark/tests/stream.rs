@@ -0,0 +1,75 @@
+// every format streams records and reads them back, one at a time or all at once
+use benchmark::fixtures::sample_records;
+use benchmark::stream::{encode_batch, read_records, RecordReader, MAX_RECORD_LEN};
+use benchmark::formats::Bincode;
+use benchmark::zero_copy::{Plain, ZeroCopy};
+use std::io::ErrorKind;
+use benchmark::{for_each_format, for_each_zero_copy_format, Codec, Format, FormatVisitor, SampleData};
+use benchmark::{ZeroCopyFormat, ZeroCopyVisitor};
+
+struct Check(Vec<SampleData>);
+
+impl Check {
+    fn run<C: Codec>(&self) {
+        let bytes = encode_batch::<C, _>(&self.0);
+        assert_eq!(read_records::<C, SampleData, _>(&bytes[..]).unwrap(), self.0, "{}", C::NAME);
+
+        let mut reader = RecordReader::<C, SampleData, _>::new(&bytes[..]);
+        assert_eq!(reader.next().unwrap().unwrap(), self.0[0], "{}", C::NAME);
+        assert_eq!(reader.count(), self.0.len() - 1, "{}", C::NAME);
+
+        // cut inside the last record
+        let cut = &bytes[..bytes.len() - 1];
+        assert!(read_records::<C, SampleData, _>(cut).is_err(), "{}", C::NAME);
+        assert!(read_records::<C, SampleData, _>(&[][..]).unwrap().is_empty());
+    }
+}
+
+impl FormatVisitor for Check {
+    fn visit<F: Format>(&mut self) {
+        self.run::<Plain<F>>();
+    }
+}
+
+impl ZeroCopyVisitor for Check {
+    fn visit<Z: ZeroCopyFormat>(&mut self) {
+        self.run::<ZeroCopy<Z>>();
+    }
+}
+
+#[test]
+fn records_round_trip_through_a_stream() {
+    // the last record is over 127 bytes, its length takes two LEB128 bytes
+    let mut check = Check(sample_records(300));
+    check.0[299].values = vec![7; 200];
+    for_each_format(&mut check);
+    for_each_zero_copy_format(&mut check);
+}
+
+// LEB128 of n
+fn prefix(mut n: usize) -> Vec<u8> {
+    let mut out = vec![];
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+    out
+}
+
+#[test]
+fn oversized_length_prefix_is_rejected() {
+    // no record bytes follow, the length alone has to fail
+    for len in [MAX_RECORD_LEN + 1, usize::MAX] {
+        let err = read_records::<Plain<Bincode>, SampleData, _>(&prefix(len)[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", len);
+    }
+
+    let records = sample_records(2);
+    let bytes = encode_batch::<Plain<Bincode>, _>(&records);
+    let len = bytes[0] as usize;
+    let mut reader = RecordReader::<Plain<Bincode>, SampleData, _>::new(&bytes[..]).with_max_len(len);
+    assert_eq!(reader.next().unwrap().unwrap(), records[0]);
+    let mut reader = RecordReader::<Plain<Bincode>, SampleData, _>::new(&bytes[..]).with_max_len(len - 1);
+    assert_eq!(reader.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+}
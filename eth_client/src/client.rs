@@ -0,0 +1,151 @@
+use ethers::signers::Signer;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::Bytes;
+use std::time::Duration;
+use web3::transports::{Http, WebSocket};
+use web3::types::{Address, BlockNumber, TransactionReceipt, H256, U256};
+use web3::{Transport, Web3};
+
+use crate::env;
+use crate::error::{Error, Result};
+use crate::fees::{Fees, Speed, FEE_HISTORY_BLOCKS};
+use crate::receipt::{Outcome, Waiter};
+use crate::signer::sign_raw;
+use crate::tx::{max_cost, TxBuilder};
+
+// json-rpc method not found, what a node without eth_feeHistory answers
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// typed wrapper around Web3 for what our binaries do: read account state, send
+// signed transactions and wait for them. web3() is there for contracts and
+// subscriptions
+#[derive(Clone, Debug)]
+pub struct Client<T: Transport> {
+    web3: Web3<T>,
+    poll_interval: Duration,
+}
+
+impl Client<Http> {
+    pub fn http(url: &str) -> Result<Self> {
+        Ok(Client::new(Http::new(url)?))
+    }
+
+    // ETHEREUM_RPC_URL
+    pub fn from_env() -> Result<Self> {
+        Client::http(&env::var(env::RPC_URL)?)
+    }
+}
+
+impl Client<WebSocket> {
+    pub async fn ws(url: &str) -> Result<Self> {
+        Ok(Client::new(WebSocket::new(url).await?))
+    }
+
+    // ETHEREUM_WS_URL
+    pub async fn ws_from_env() -> Result<Self> {
+        Client::ws(&env::var(env::WS_URL)?).await
+    }
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(transport: T) -> Self {
+        Client { web3: Web3::new(transport), poll_interval: Duration::from_millis(500) }
+    }
+
+    // how often receipts are polled
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    pub fn web3(&self) -> &Web3<T> {
+        &self.web3
+    }
+
+    pub async fn balance(&self, address: Address) -> Result<U256> {
+        Ok(self.web3.eth().balance(address, None).await?)
+    }
+
+    // balance formatted in ether
+    pub async fn balance_eth(&self, address: Address) -> Result<String> {
+        let balance = self.balance(address).await?;
+        Ok(ethers::utils::format_units(balance, "ether").unwrap_or_default())
+    }
+
+    pub async fn nonce(&self, address: Address) -> Result<U256> {
+        Ok(self.web3.eth().transaction_count(address, None).await?)
+    }
+
+    pub async fn chain_id(&self) -> Result<u64> {
+        Ok(self.web3.eth().chain_id().await?.as_u64())
+    }
+
+    pub async fn gas_price(&self) -> Result<U256> {
+        Ok(self.web3.eth().gas_price().await?)
+    }
+
+    // eip-1559 fees from the fee history, a legacy gas price when the node
+    // has no fee history or the chain no base fee. any other rpc error, a
+    // rate limit or a rejected key, is returned
+    pub async fn fees(&self, speed: Speed) -> Result<Fees> {
+        let history = self.web3.eth().fee_history(FEE_HISTORY_BLOCKS.into(), BlockNumber::Latest, Some(vec![speed.percentile()]));
+        match history.await {
+            Ok(history) => {
+                if let Some(fees) = Fees::from_history(&history) {
+                    return Ok(fees);
+                }
+            }
+            Err(web3::Error::Rpc(e)) if e.code.code() == METHOD_NOT_FOUND => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(Fees::Legacy { gas_price: self.gas_price().await? })
+    }
+
+    // fills in what the builder left open from the node
+    pub async fn prepare(&self, from: Address, tx: &TxBuilder) -> Result<TypedTransaction> {
+        let nonce = match tx.nonce {
+            Some(nonce) => nonce,
+            None => self.nonce(from).await?,
+        };
+        let fees = match tx.fees {
+            Some(fees) => fees,
+            None => self.fees(tx.speed).await?,
+        };
+        Ok(tx.build(from, nonce, fees, self.chain_id().await?))
+    }
+
+    // error when the sender can not pay for value and gas
+    pub async fn ensure_balance(&self, tx: &TypedTransaction) -> Result<()> {
+        let from = tx.from().copied().unwrap_or_default();
+        let (balance, needed) = (self.balance(from).await?, max_cost(tx));
+        if balance < needed {
+            return Err(Error::InsufficientBalance { balance, needed });
+        }
+        Ok(())
+    }
+
+    pub async fn send_raw(&self, raw: Bytes) -> Result<H256> {
+        Ok(self.web3.eth().send_raw_transaction(web3::types::Bytes(raw.to_vec())).await?)
+    }
+
+    // prepare, check the balance, sign and send
+    pub async fn send<S: Signer>(&self, signer: &S, tx: &TxBuilder) -> Result<H256> {
+        let tx = self.prepare(signer.address(), tx).await?;
+        self.ensure_balance(&tx).await?;
+        self.send_raw(sign_raw(signer, &tx).await?).await
+    }
+
+    pub async fn receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
+        Ok(self.web3.eth().transaction_receipt(hash).await?)
+    }
+
+    // waits with the default timeout for the block the transaction lands in,
+    // use a Waiter for more confirmations or another timeout
+    pub async fn wait_for_receipt(&self, hash: H256) -> Result<Outcome> {
+        Waiter::default().wait(self, hash).await
+    }
+}
@@ -0,0 +1,41 @@
+// settings every binary reads from .env
+use dotenv::dotenv;
+use ethers::signers::LocalWallet;
+use web3::types::Address;
+
+use crate::error::{Error, Result};
+use crate::fees::Speed;
+use crate::signer::parse_wallet;
+
+pub const RPC_URL: &str = "ETHEREUM_RPC_URL";
+pub const WS_URL: &str = "ETHEREUM_WS_URL";
+pub const SENDER_PRIVATE_KEY: &str = "SENDER_PRIVATE_KEY";
+pub const CONTRACT_ADDRESS: &str = "CONTRACT_ADDRESS";
+// slow, standard or fast, how much tip transactions offer
+pub const TX_SPEED: &str = "TX_SPEED";
+
+// loads .env first, variables already set win over it
+pub fn var(name: &str) -> Result<String> {
+    dotenv().ok();
+    std::env::var(name).map_err(|_| Error::MissingEnv(name.to_string()))
+}
+
+pub fn wallet(name: &str) -> Result<LocalWallet> {
+    parse_wallet(&var(name)?)
+}
+
+pub fn address(name: &str) -> Result<Address> {
+    parse_address(&var(name)?)
+}
+
+// TX_SPEED, standard when not set
+pub fn speed() -> Result<Speed> {
+    match var(TX_SPEED) {
+        Ok(speed) => speed.parse(),
+        Err(_) => Ok(Speed::default()),
+    }
+}
+
+pub fn parse_address(address: &str) -> Result<Address> {
+    address.trim().parse().map_err(|_| Error::InvalidAddress(address.to_string()))
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+use web3::types::U256;
+
+#[derive(Debug)]
+pub enum Error {
+    // variable not set in the environment or .env
+    MissingEnv(String),
+    InvalidKey(String),
+    InvalidAddress(String),
+    // not slow, standard or fast
+    InvalidSpeed(String),
+    Rpc(web3::Error),
+    // abi encoding or a contract call
+    Contract(String),
+    Signing(String),
+    InsufficientBalance { balance: U256, needed: U256 },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingEnv(name) => write!(f, "missing {} in environment or .env", name),
+            Error::InvalidKey(e) => write!(f, "invalid private key: {}", e),
+            Error::InvalidAddress(address) => write!(f, "invalid address: {}", address),
+            Error::InvalidSpeed(speed) => write!(f, "invalid speed {}, expected slow, standard or fast", speed),
+            Error::Rpc(e) => write!(f, "rpc error: {}", e),
+            Error::Contract(e) => write!(f, "contract error: {}", e),
+            Error::Signing(e) => write!(f, "signing failed: {}", e),
+            Error::InsufficientBalance { balance, needed } => write!(
+                f,
+                "insufficient balance: have {} ETH, need {} ETH",
+                ethers::utils::format_units(*balance, "ether").unwrap_or_default(),
+                ethers::utils::format_units(*needed, "ether").unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<web3::Error> for Error {
+    fn from(e: web3::Error) -> Self {
+        Error::Rpc(e)
+    }
+}
+
+impl From<web3::ethabi::Error> for Error {
+    fn from(e: web3::ethabi::Error) -> Self {
+        Error::Contract(e.to_string())
+    }
+}
+
+impl From<web3::contract::Error> for Error {
+    fn from(e: web3::contract::Error) -> Self {
+        Error::Contract(e.to_string())
+    }
+}
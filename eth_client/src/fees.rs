@@ -0,0 +1,82 @@
+// eip-1559 fees from eth_feeHistory: the tip is a percentile of what recent
+// blocks paid, the max fee leaves room for the base fee to double. chains
+// without a base fee get a legacy gas price
+use std::fmt;
+use std::str::FromStr;
+use web3::types::{FeeHistory, U256};
+
+use crate::error::Error;
+
+// blocks of history the tip is taken from
+pub const FEE_HISTORY_BLOCKS: u64 = 10;
+// tip when recent blocks were empty, 1 gwei
+pub const DEFAULT_PRIORITY_FEE: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Speed {
+    Slow,
+    #[default]
+    Standard,
+    Fast,
+}
+
+impl Speed {
+    // reward percentile asked from eth_feeHistory
+    pub fn percentile(&self) -> f64 {
+        match self {
+            Speed::Slow => 10.0,
+            Speed::Standard => 50.0,
+            Speed::Fast => 90.0,
+        }
+    }
+}
+
+impl FromStr for Speed {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.trim().to_lowercase().as_str() {
+            "slow" => Ok(Speed::Slow),
+            "standard" => Ok(Speed::Standard),
+            "fast" => Ok(Speed::Fast),
+            _ => Err(Error::InvalidSpeed(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Speed::Slow => write!(f, "slow"),
+            Speed::Standard => write!(f, "standard"),
+            Speed::Fast => write!(f, "fast"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fees {
+    Legacy { gas_price: U256 },
+    Eip1559 { max_fee_per_gas: U256, max_priority_fee_per_gas: U256 },
+}
+
+impl Fees {
+    // None when the chain has no base fee
+    pub fn from_history(history: &FeeHistory) -> Option<Fees> {
+        // the last entry is the base fee of the next block
+        let base_fee = history.base_fee_per_gas.last().copied().filter(|fee| !fee.is_zero())?;
+
+        // empty blocks report a zero tip, they say nothing about the market
+        let mut tips: Vec<U256> = history
+            .reward
+            .iter()
+            .flatten()
+            .filter_map(|block| block.first().copied())
+            .filter(|tip| !tip.is_zero())
+            .collect();
+        tips.sort();
+        let tip = tips.get(tips.len() / 2).copied().unwrap_or_else(|| DEFAULT_PRIORITY_FEE.into());
+
+        Some(Fees::Eip1559 { max_fee_per_gas: base_fee * 2 + tip, max_priority_fee_per_gas: tip })
+    }
+}
@@ -0,0 +1,17 @@
+// ethereum client shared by the wallet, the event listener and the backends:
+// settings from .env, a typed client over web3, a transaction builder with
+// eip-1559 fees, signing through ethers' Signer trait and a receipt waiter
+pub mod client;
+pub mod env;
+pub mod error;
+pub mod fees;
+pub mod receipt;
+pub mod signer;
+pub mod tx;
+
+pub use client::Client;
+pub use error::{Error, Result};
+pub use fees::{Fees, Speed};
+pub use receipt::{Outcome, Waiter};
+pub use signer::{parse_wallet, random_wallet, sign_raw};
+pub use tx::TxBuilder;
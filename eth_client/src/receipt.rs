@@ -0,0 +1,198 @@
+// waiting for a sent transaction without hanging forever: gives up after a
+// timeout, waits for a number of confirmations and notices when the nonce was
+// used by another transaction or the node forgot about ours
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+use web3::ethabi::ParamType;
+use web3::types::{Address, BlockId, CallRequest, TransactionId, TransactionReceipt, H256, U256};
+use web3::Transport;
+
+use crate::client::Client;
+use crate::error::Result;
+
+// Error(string), what require and revert with a message encode
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Mined(TransactionReceipt),
+    Reverted { receipt: TransactionReceipt, reason: Option<String> },
+    // another transaction from the same sender took the nonce
+    Replaced { nonce: U256 },
+    // the node no longer knows the transaction and the nonce is still free
+    Dropped,
+    TimedOut,
+}
+
+impl Outcome {
+    // the receipt for mined and reverted transactions
+    pub fn receipt(&self) -> Option<&TransactionReceipt> {
+        match self {
+            Outcome::Mined(receipt) | Outcome::Reverted { receipt, .. } => Some(receipt),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Mined(receipt) => write!(f, "mined in block {}", receipt.block_number.unwrap_or_default()),
+            Outcome::Reverted { reason: Some(reason), .. } => write!(f, "reverted: {}", reason),
+            Outcome::Reverted { reason: None, .. } => write!(f, "reverted"),
+            Outcome::Replaced { nonce } => write!(f, "replaced, nonce {} was used by another transaction", nonce),
+            Outcome::Dropped => write!(f, "dropped by the node"),
+            Outcome::TimedOut => write!(f, "timed out waiting for the receipt"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Waiter {
+    pub timeout: Duration,
+    // 1 is the block the transaction is in
+    pub confirmations: u64,
+    // polls in a row the node has to not know the transaction before it counts
+    // as dropped. load balanced rpc providers miss fresh transactions now and then
+    pub dropped_after: u32,
+    // a transaction the node never showed us is only dropped after this long,
+    // providers can take a while before a fresh one shows up at all
+    pub unseen_grace: Duration,
+}
+
+impl Default for Waiter {
+    fn default() -> Self {
+        Waiter { timeout: Duration::from_secs(300), confirmations: 1, dropped_after: 3, unseen_grace: Duration::from_secs(60) }
+    }
+}
+
+impl Waiter {
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    pub fn dropped_after(mut self, polls: u32) -> Self {
+        self.dropped_after = polls.max(1);
+        self
+    }
+
+    pub fn unseen_grace(mut self, grace: Duration) -> Self {
+        self.unseen_grace = grace;
+        self
+    }
+
+    // a failed request only costs that poll, rate limits and timeouts are
+    // common on public endpoints. the error is returned at the timeout when
+    // the last poll failed too
+    pub async fn wait<T: Transport>(&self, client: &Client<T>, hash: H256) -> Result<Outcome> {
+        let mut state = Polls { started: Instant::now(), sent: None, missing: 0 };
+        let deadline = state.started + self.timeout;
+        loop {
+            let error = match self.poll(client, hash, &mut state).await {
+                Ok(Some(outcome)) => return Ok(outcome),
+                Ok(None) => None,
+                Err(e) => Some(e),
+            };
+            if Instant::now() >= deadline {
+                return error.map_or(Ok(Outcome::TimedOut), Err);
+            }
+            tokio::time::sleep(client.poll_interval()).await;
+        }
+    }
+
+    // one look at the transaction, None while it is still pending
+    async fn poll<T: Transport>(&self, client: &Client<T>, hash: H256, state: &mut Polls) -> Result<Option<Outcome>> {
+        let eth = client.web3().eth();
+        match client.receipt(hash).await? {
+            Some(receipt) if receipt.block_number.is_some() => {
+                let block = receipt.block_number.unwrap_or_default().as_u64();
+                let latest = eth.block_number().await?.as_u64();
+                if latest + 1 >= block + self.confirmations {
+                    return Ok(Some(if receipt.status == Some(0.into()) {
+                        let reason = revert_reason(client, &receipt).await?;
+                        Outcome::Reverted { receipt, reason }
+                    } else {
+                        Outcome::Mined(receipt)
+                    }));
+                }
+            }
+            _ => match eth.transaction(TransactionId::Hash(hash)).await? {
+                Some(tx) => {
+                    state.missing = 0;
+                    state.sent = tx.from.map(|from| (from, tx.nonce));
+                }
+                None => {
+                    state.missing += 1;
+                    if state.missing >= self.dropped_after {
+                        match state.sent {
+                            // our own transaction being mined also takes the nonce, and a
+                            // lagging node can answer the receipt above with null
+                            Some((from, nonce)) if client.nonce(from).await? > nonce => match client.receipt(hash).await? {
+                                Some(_) => {}
+                                None => return Ok(Some(Outcome::Replaced { nonce })),
+                            },
+                            Some(_) => return Ok(Some(Outcome::Dropped)),
+                            None if state.started.elapsed() >= self.unseen_grace => return Ok(Some(Outcome::Dropped)),
+                            None => {}
+                        }
+                    }
+                }
+            },
+        }
+        Ok(None)
+    }
+}
+
+// what wait carries from one poll to the next
+struct Polls {
+    started: Instant,
+    // sender and nonce, known once the node has shown us the transaction
+    sent: Option<(Address, U256)>,
+    // polls in a row the node did not know the transaction, a failed request is not a miss
+    missing: u32,
+}
+
+// replays the transaction as a call on the state before its block, the node
+// answers with the revert data. transactions before it in the same block are
+// not applied, so the replay can still differ. None when the replay does not
+// revert or gives no message
+async fn revert_reason<T: Transport>(client: &Client<T>, receipt: &TransactionReceipt) -> Result<Option<String>> {
+    let eth = client.web3().eth();
+    let tx = match eth.transaction(TransactionId::Hash(receipt.transaction_hash)).await? {
+        Some(tx) => tx,
+        None => return Ok(None),
+    };
+    let call = CallRequest {
+        from: tx.from,
+        to: tx.to,
+        gas: Some(tx.gas),
+        value: Some(tx.value),
+        data: Some(tx.input),
+        ..Default::default()
+    };
+    let block = receipt.block_number.map(|number| BlockId::Number(number.saturating_sub(1.into()).into()));
+    match eth.call(call, block).await {
+        Ok(_) => Ok(None),
+        Err(web3::Error::Rpc(e)) => {
+            let data = e.data.as_ref().and_then(|data| data.as_str()).and_then(|data| ethers::utils::hex::decode(data).ok());
+            let message = e.message.strip_prefix("execution reverted: ").map(str::to_string);
+            Ok(data.and_then(|data| decode_revert(&data)).or(message))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+// the message of an Error(string) revert
+pub fn decode_revert(data: &[u8]) -> Option<String> {
+    if data.len() < 4 || data[..4] != ERROR_SELECTOR {
+        return None;
+    }
+    web3::ethabi::decode(&[ParamType::String], &data[4..]).ok()?.pop()?.into_string()
+}
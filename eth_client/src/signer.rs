@@ -0,0 +1,26 @@
+// signing goes through ethers' Signer trait, so a LocalWallet, a hardware wallet
+// or a remote signer can all send transactions through the client
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::Bytes;
+
+use crate::error::{Error, Result};
+
+pub fn parse_wallet(private_key: &str) -> Result<LocalWallet> {
+    private_key.trim().parse().map_err(|e: ethers::signers::WalletError| Error::InvalidKey(e.to_string()))
+}
+
+pub fn random_wallet() -> LocalWallet {
+    LocalWallet::new(&mut ethers::core::rand::thread_rng())
+}
+
+// private key as hex without 0x, how the binaries print it
+pub fn private_key_hex(wallet: &LocalWallet) -> String {
+    ethers::utils::hex::encode(wallet.signer().to_bytes())
+}
+
+// signed rlp, ready for eth_sendRawTransaction. the chain id must already be set
+pub async fn sign_raw<S: Signer>(signer: &S, tx: &TypedTransaction) -> Result<Bytes> {
+    let signature = signer.sign_transaction(tx).await.map_err(|e| Error::Signing(e.to_string()))?;
+    Ok(tx.rlp_signed(&signature))
+}
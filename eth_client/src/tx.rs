@@ -0,0 +1,104 @@
+// what the caller decides about a transaction. nonce, chain id and fees are
+// filled in by Client::prepare from the node unless set here
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Bytes, Eip1559TransactionRequest, TransactionRequest};
+use web3::types::{Address, U256};
+
+use crate::fees::{Fees, Speed};
+
+pub const TRANSFER_GAS: u64 = 21_000;
+// what the binaries give a simple contract call
+pub const CALL_GAS: u64 = 100_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxBuilder {
+    pub to: Address,
+    pub value: U256,
+    pub data: Option<Bytes>,
+    pub gas: U256,
+    pub fees: Option<Fees>,
+    // used when fees are left to the node
+    pub speed: Speed,
+    pub nonce: Option<U256>,
+}
+
+impl TxBuilder {
+    pub fn transfer(to: Address, value: U256) -> Self {
+        TxBuilder { to, value, data: None, gas: TRANSFER_GAS.into(), fees: None, speed: Speed::default(), nonce: None }
+    }
+
+    // contract call with abi encoded input, no ether sent
+    pub fn call(to: Address, data: impl Into<Bytes>) -> Self {
+        TxBuilder { to, value: U256::zero(), data: Some(data.into()), gas: CALL_GAS.into(), fees: None, speed: Speed::default(), nonce: None }
+    }
+
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn gas(mut self, gas: U256) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    // forces a legacy transaction
+    pub fn gas_price(mut self, gas_price: U256) -> Self {
+        self.fees = Some(Fees::Legacy { gas_price });
+        self
+    }
+
+    pub fn fees(mut self, fees: Fees) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    pub fn speed(mut self, speed: Speed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    // type 2 for eip-1559 fees, legacy for a gas price
+    pub fn build(&self, from: Address, nonce: U256, fees: Fees, chain_id: u64) -> TypedTransaction {
+        let mut tx: TypedTransaction = match fees {
+            Fees::Legacy { gas_price } => TransactionRequest {
+                from: Some(from),
+                to: Some(self.to.into()),
+                value: Some(self.value),
+                gas: Some(self.gas),
+                gas_price: Some(gas_price),
+                nonce: Some(nonce),
+                data: self.data.clone(),
+                ..Default::default()
+            }
+            .into(),
+            Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => Eip1559TransactionRequest {
+                from: Some(from),
+                to: Some(self.to.into()),
+                value: Some(self.value),
+                gas: Some(self.gas),
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                nonce: Some(nonce),
+                data: self.data.clone(),
+                ..Default::default()
+            }
+            .into(),
+        };
+        tx.set_chain_id(chain_id);
+        tx
+    }
+}
+
+// the most the transaction can take from the sender: value plus all the gas,
+// at the max fee for type 2
+pub fn max_cost(tx: &TypedTransaction) -> U256 {
+    let value = tx.value().copied().unwrap_or_default();
+    let gas = tx.gas().copied().unwrap_or_default();
+    value + gas * tx.gas_price().unwrap_or_default()
+}
@@ -0,0 +1,178 @@
+// the client against web3's test transport, which answers requests in order
+use eth_client::tx::max_cost;
+use eth_client::{parse_wallet, sign_raw, Client, Error, Fees, Speed, TxBuilder};
+use ethers::signers::Signer;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::rlp::Rlp;
+use serde_json::json;
+use web3::transports::test::TestTransport;
+use web3::types::{Address, U256};
+use web3::{RequestId, Transport};
+
+const KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+// next base fee 10, tips of 2 and 4 at the asked percentile
+fn fee_history() -> serde_json::Value {
+    json!({ "oldestBlock": "0x1", "baseFeePerGas": ["0x8", "0xa"], "gasUsedRatio": [0.5], "reward": [["0x2"], ["0x4"]] })
+}
+
+#[tokio::test]
+async fn prepare_fills_nonce_fees_and_chain_id() {
+    let mut transport = TestTransport::default();
+    transport.add_response(json!("0x7")); // eth_getTransactionCount
+    transport.add_response(fee_history()); // eth_feeHistory
+    transport.add_response(json!("0xaa36a7")); // eth_chainId, sepolia
+    let client = Client::new(transport.clone());
+
+    let from = Address::repeat_byte(1);
+    let tx = client.prepare(from, &TxBuilder::transfer(Address::repeat_byte(2), U256::exp10(16))).await.unwrap();
+    let TypedTransaction::Eip1559(inner) = &tx else { panic!("expected a type 2 transaction, got {:?}", tx) };
+    assert_eq!(inner.max_priority_fee_per_gas, Some(4.into()));
+    assert_eq!(inner.max_fee_per_gas, Some((2 * 10 + 4).into()));
+    assert_eq!(tx.nonce(), Some(&U256::from(7)));
+    assert_eq!(tx.chain_id(), Some(11155111u64.into()));
+    assert_eq!(tx.gas(), Some(&U256::from(21000)));
+    assert_eq!(max_cost(&tx), U256::exp10(16) + U256::from(21000 * 24));
+
+    transport.assert_request("eth_getTransactionCount", &[format!("\"{:?}\"", from), "\"latest\"".to_string()]);
+    transport.assert_request("eth_feeHistory", &["\"0xa\"".to_string(), "\"latest\"".to_string(), "[50.0]".to_string()]);
+    transport.assert_request("eth_chainId", &[]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test]
+async fn legacy_without_base_fee() {
+    let mut transport = TestTransport::default();
+    transport.add_response(json!("0x7"));
+    transport.add_response(json!({ "oldestBlock": "0x1", "baseFeePerGas": ["0x0", "0x0"], "gasUsedRatio": [0.5], "reward": [["0x0"]] }));
+    transport.add_response(json!("0x3b9aca00")); // eth_gasPrice, 1 gwei
+    transport.add_response(json!("0x1"));
+    let client = Client::new(transport.clone());
+
+    let tx = client.prepare(Address::repeat_byte(1), &TxBuilder::transfer(Address::repeat_byte(2), 1.into())).await.unwrap();
+    assert!(matches!(tx, TypedTransaction::Legacy(_)));
+    assert_eq!(tx.gas_price(), Some(U256::from(1_000_000_000u64)));
+}
+
+// the test transport, except that one method is answered with an rpc error
+#[derive(Debug, Clone)]
+struct Failing {
+    inner: TestTransport,
+    method: &'static str,
+    code: i64,
+}
+
+impl Transport for Failing {
+    type Out = <TestTransport as Transport>::Out;
+
+    fn prepare(&self, method: &str, params: Vec<jsonrpc_core::Value>) -> (RequestId, jsonrpc_core::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: jsonrpc_core::Call) -> Self::Out {
+        match &request {
+            jsonrpc_core::Call::MethodCall(call) if call.method == self.method => {
+                let error = jsonrpc_core::Error { code: self.code.into(), message: "failed".to_string(), data: None };
+                Box::pin(async move { Err(web3::Error::Rpc(error)) })
+            }
+            _ => self.inner.send(id, request),
+        }
+    }
+}
+
+#[tokio::test]
+async fn legacy_without_fee_history() {
+    let mut transport = TestTransport::default();
+    transport.add_response(json!("0x3b9aca00")); // eth_gasPrice
+    let client = Client::new(Failing { inner: transport.clone(), method: "eth_feeHistory", code: -32601 });
+
+    assert_eq!(client.fees(Speed::Standard).await.unwrap(), Fees::Legacy { gas_price: 1_000_000_000u64.into() });
+    transport.assert_request("eth_feeHistory", &["\"0xa\"".to_string(), "\"latest\"".to_string(), "[50.0]".to_string()]);
+    transport.assert_request("eth_gasPrice", &[]);
+    transport.assert_no_more_requests();
+}
+
+// a rate limit is not a node without fee history, no gas price is asked for
+#[tokio::test]
+async fn other_fee_history_errors_are_returned() {
+    let mut transport = TestTransport::default();
+    let client = Client::new(Failing { inner: transport.clone(), method: "eth_feeHistory", code: -32005 });
+
+    match client.fees(Speed::Standard).await {
+        Err(Error::Rpc(web3::Error::Rpc(e))) => assert_eq!(e.code.code(), -32005),
+        other => panic!("expected the rpc error, got {:?}", other),
+    }
+    transport.assert_request("eth_feeHistory", &["\"0xa\"".to_string(), "\"latest\"".to_string(), "[50.0]".to_string()]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test]
+async fn set_fields_are_not_fetched() {
+    let mut transport = TestTransport::default();
+    transport.add_response(json!("0x1"));
+    let client = Client::new(transport.clone());
+
+    let builder = TxBuilder::call(Address::repeat_byte(2), vec![1, 2, 3]).nonce(3.into()).gas_price(5.into());
+    let tx = client.prepare(Address::repeat_byte(1), &builder).await.unwrap();
+    assert_eq!(tx.nonce(), Some(&U256::from(3)));
+    assert_eq!(tx.value(), Some(&U256::zero()));
+    assert_eq!(tx.data().map(|d| d.to_vec()), Some(vec![1, 2, 3]));
+    transport.assert_request("eth_chainId", &[]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test]
+async fn send_refuses_without_balance() {
+    let mut transport = TestTransport::default();
+    for response in ["0x0", "0x1", "0x5"] {
+        transport.add_response(json!(response)); // nonce, chain id, balance
+    }
+    let client = Client::new(transport.clone());
+    let wallet = parse_wallet(KEY).unwrap();
+
+    match client.send(&wallet, &TxBuilder::transfer(Address::repeat_byte(2), 100.into()).gas_price(1.into())).await {
+        Err(Error::InsufficientBalance { balance, needed }) => {
+            assert_eq!(balance, 5.into());
+            assert_eq!(needed, (100 + 21000).into());
+        }
+        other => panic!("expected insufficient balance, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn send_signs_for_the_chain() {
+    let mut transport = TestTransport::default();
+    transport.add_response(json!("0x0"));
+    transport.add_response(fee_history());
+    transport.add_response(json!("0x1"));
+    transport.add_response(json!("0xffffffff"));
+    let hash = format!("0x{}", "ab".repeat(32));
+    transport.add_response(json!(hash));
+    let client = Client::new(transport.clone());
+    let wallet = parse_wallet(KEY).unwrap();
+
+    let builder = TxBuilder::transfer(Address::repeat_byte(2), 100.into());
+    let sent = client.send(&wallet, &builder).await.unwrap();
+    assert_eq!(format!("{:?}", sent), hash);
+
+    // signatures are deterministic, so the raw transaction can be rebuilt here
+    let fees = Fees::Eip1559 { max_fee_per_gas: 24.into(), max_priority_fee_per_gas: 4.into() };
+    let tx = builder.build(wallet.address(), 0.into(), fees, 1);
+    let raw = sign_raw(&wallet, &tx).await.unwrap();
+    let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+    assert_eq!(signature.recover(decoded.sighash()).unwrap(), wallet.address());
+
+    let from = format!("\"{:?}\"", wallet.address());
+    transport.assert_request("eth_getTransactionCount", &[from.clone(), "\"latest\"".to_string()]);
+    transport.assert_request("eth_feeHistory", &["\"0xa\"".to_string(), "\"latest\"".to_string(), "[50.0]".to_string()]);
+    transport.assert_request("eth_chainId", &[]);
+    transport.assert_request("eth_getBalance", &[from, "\"latest\"".to_string()]);
+    transport.assert_request("eth_sendRawTransaction", &[format!("\"{}\"", raw)]);
+}
+
+#[test]
+fn bad_input_is_an_error() {
+    assert!(matches!(parse_wallet("not a key"), Err(Error::InvalidKey(_))));
+    assert!(matches!(eth_client::env::parse_address("0x12"), Err(Error::InvalidAddress(_))));
+    assert!(matches!(eth_client::env::var("ETH_CLIENT_TEST_UNSET"), Err(Error::MissingEnv(_))));
+}
@@ -0,0 +1,45 @@
+use eth_client::{Error, Fees, Speed};
+use web3::types::{BlockNumber, FeeHistory, U256};
+
+fn history(base_fees: &[u64], tips: &[u64]) -> FeeHistory {
+    FeeHistory {
+        oldest_block: BlockNumber::Number(1.into()),
+        base_fee_per_gas: base_fees.iter().map(|&fee| fee.into()).collect(),
+        gas_used_ratio: vec![0.5; tips.len()],
+        reward: Some(tips.iter().map(|&tip| vec![tip.into()]).collect()),
+    }
+}
+
+#[test]
+fn max_fee_covers_a_doubled_base_fee() {
+    // the tip is the median, the max fee uses the next block's base fee
+    let fees = Fees::from_history(&history(&[50, 90, 100], &[3, 1, 2])).unwrap();
+    assert_eq!(fees, Fees::Eip1559 { max_fee_per_gas: (2 * 100 + 2).into(), max_priority_fee_per_gas: 2.into() });
+}
+
+#[test]
+fn empty_blocks_do_not_pull_the_tip_down() {
+    let fees = Fees::from_history(&history(&[100, 100, 100], &[0, 0, 7])).unwrap();
+    assert_eq!(fees, Fees::Eip1559 { max_fee_per_gas: 207.into(), max_priority_fee_per_gas: 7.into() });
+
+    let fees = Fees::from_history(&history(&[100, 100], &[0])).unwrap();
+    let tip = U256::from(eth_client::fees::DEFAULT_PRIORITY_FEE);
+    assert_eq!(fees, Fees::Eip1559 { max_fee_per_gas: U256::from(200) + tip, max_priority_fee_per_gas: tip });
+}
+
+#[test]
+fn no_base_fee_means_legacy() {
+    assert_eq!(Fees::from_history(&history(&[0, 0], &[5])), None);
+    assert_eq!(Fees::from_history(&history(&[], &[])), None);
+}
+
+#[test]
+fn speeds_parse() {
+    for speed in [Speed::Slow, Speed::Standard, Speed::Fast] {
+        assert_eq!(speed.to_string().parse::<Speed>().unwrap(), speed);
+    }
+    assert_eq!(" FAST ".parse::<Speed>().unwrap(), Speed::Fast);
+    assert!(matches!("ludicrous".parse::<Speed>(), Err(Error::InvalidSpeed(_))));
+    assert!(Speed::Slow.percentile() < Speed::Standard.percentile());
+    assert!(Speed::Standard.percentile() < Speed::Fast.percentile());
+}
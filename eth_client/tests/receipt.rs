@@ -0,0 +1,243 @@
+// the waiter against web3's test transport. time is paused, so the sleeps
+// between polls pass instantly
+use eth_client::receipt::decode_revert;
+use eth_client::{Client, Error, Outcome, Waiter};
+use serde_json::{json, Value};
+use std::time::Duration;
+use web3::ethabi::Token;
+use web3::transports::test::TestTransport;
+use web3::types::{Address, CallRequest, Transaction, TransactionReceipt, H256};
+use web3::{RequestId, Transport};
+
+fn hash() -> H256 {
+    H256::repeat_byte(0xab)
+}
+
+fn receipt(block: u64, status: u64) -> TransactionReceipt {
+    TransactionReceipt {
+        transaction_hash: hash(),
+        block_number: Some(block.into()),
+        status: Some(status.into()),
+        ..Default::default()
+    }
+}
+
+fn pending(nonce: u64) -> Value {
+    json!(Transaction { hash: hash(), nonce: nonce.into(), from: Some(Address::repeat_byte(1)), ..Default::default() })
+}
+
+fn responses(transport: &mut TestTransport, values: Vec<Value>) {
+    for value in values {
+        transport.add_response(value);
+    }
+}
+
+// the test transport, except that the requests `fails` picks by number, from
+// 1, are answered with a rate limit error
+#[derive(Debug, Clone)]
+struct Flaky {
+    inner: TestTransport,
+    fails: fn(RequestId) -> bool,
+}
+
+impl Transport for Flaky {
+    type Out = <TestTransport as Transport>::Out;
+
+    fn prepare(&self, method: &str, params: Vec<jsonrpc_core::Value>) -> (RequestId, jsonrpc_core::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: jsonrpc_core::Call) -> Self::Out {
+        if (self.fails)(id) {
+            let error = jsonrpc_core::Error { code: (-32005).into(), message: "rate limited".to_string(), data: None };
+            return Box::pin(async move { Err(web3::Error::Rpc(error)) });
+        }
+        self.inner.send(id, request)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn mined_once_in_a_block() {
+    let mut transport = TestTransport::default();
+    responses(&mut transport, vec![json!(receipt(5, 1)), json!("0x5")]);
+    let client = Client::new(transport.clone());
+
+    assert_eq!(client.wait_for_receipt(hash()).await.unwrap(), Outcome::Mined(receipt(5, 1)));
+    transport.assert_request("eth_getTransactionReceipt", &[format!("\"{:?}\"", hash())]);
+    transport.assert_request("eth_blockNumber", &[]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_for_confirmations() {
+    let mut transport = TestTransport::default();
+    responses(&mut transport, vec![json!(receipt(5, 1)), json!("0x5"), json!(receipt(5, 1)), json!("0x7")]);
+    let client = Client::new(transport.clone());
+
+    let outcome = Waiter::default().confirmations(3).wait(&client, hash()).await.unwrap();
+    assert_eq!(outcome, Outcome::Mined(receipt(5, 1)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_status_is_a_revert() {
+    let mut transport = TestTransport::default();
+    // receipt, block number, the transaction for the replay, the replay not reverting
+    responses(&mut transport, vec![json!(receipt(5, 0)), json!("0x5"), pending(0), json!("0x")]);
+    let client = Client::new(transport.clone());
+
+    let outcome = client.wait_for_receipt(hash()).await.unwrap();
+    assert_eq!(outcome, Outcome::Reverted { receipt: receipt(5, 0), reason: None });
+    assert_eq!(outcome.receipt(), Some(&receipt(5, 0)));
+
+    // replayed on the state before block 5
+    let call = CallRequest {
+        from: Some(Address::repeat_byte(1)),
+        gas: Some(0.into()),
+        value: Some(0.into()),
+        data: Some(Default::default()),
+        ..Default::default()
+    };
+    transport.assert_request("eth_getTransactionReceipt", &[format!("\"{:?}\"", hash())]);
+    transport.assert_request("eth_blockNumber", &[]);
+    transport.assert_request("eth_getTransactionByHash", &[format!("\"{:?}\"", hash())]);
+    transport.assert_request("eth_call", &[serde_json::to_value(call).unwrap().to_string(), "\"0x4\"".to_string()]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test(start_paused = true)]
+async fn nonce_taken_by_another_transaction() {
+    let mut transport = TestTransport::default();
+    responses(&mut transport, vec![Value::Null, pending(4)]);
+    // a single miss while the nonce has moved on is not enough
+    responses(&mut transport, vec![Value::Null, Value::Null]);
+    responses(&mut transport, vec![Value::Null, pending(4)]);
+    for _ in 0..2 {
+        responses(&mut transport, vec![Value::Null, Value::Null]);
+    }
+    // the third miss in a row, the nonce is taken and there is still no receipt
+    responses(&mut transport, vec![Value::Null, Value::Null, json!("0x5"), Value::Null]);
+    let client = Client::new(transport.clone());
+
+    let outcome = client.wait_for_receipt(hash()).await.unwrap();
+    assert_eq!(outcome, Outcome::Replaced { nonce: 4.into() });
+    assert_eq!(outcome.receipt(), None);
+    for _ in 0..6 {
+        transport.assert_request("eth_getTransactionReceipt", &[format!("\"{:?}\"", hash())]);
+        transport.assert_request("eth_getTransactionByHash", &[format!("\"{:?}\"", hash())]);
+    }
+    transport.assert_request("eth_getTransactionCount", &[format!("\"{:?}\"", Address::repeat_byte(1)), "\"latest\"".to_string()]);
+    transport.assert_request("eth_getTransactionReceipt", &[format!("\"{:?}\"", hash())]);
+    transport.assert_no_more_requests();
+}
+
+// our transaction took the nonce itself, the node was only slow to show the receipt
+#[tokio::test(start_paused = true)]
+async fn lagging_receipt_is_not_a_replacement() {
+    let mut transport = TestTransport::default();
+    responses(&mut transport, vec![Value::Null, pending(4)]);
+    for _ in 0..2 {
+        responses(&mut transport, vec![Value::Null, Value::Null]);
+    }
+    responses(&mut transport, vec![Value::Null, Value::Null, json!("0x5"), json!(receipt(5, 1))]);
+    responses(&mut transport, vec![json!(receipt(5, 1)), json!("0x5")]);
+    let client = Client::new(transport.clone());
+
+    assert_eq!(client.wait_for_receipt(hash()).await.unwrap(), Outcome::Mined(receipt(5, 1)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn forgotten_transaction_is_dropped() {
+    let mut transport = TestTransport::default();
+    responses(&mut transport, vec![Value::Null, pending(4)]);
+    for _ in 0..2 {
+        // no receipt, unknown transaction
+        responses(&mut transport, vec![Value::Null, Value::Null]);
+    }
+    // the third miss, and the nonce is still free
+    responses(&mut transport, vec![Value::Null, Value::Null, json!("0x4")]);
+    let client = Client::new(transport.clone());
+
+    assert_eq!(client.wait_for_receipt(hash()).await.unwrap(), Outcome::Dropped);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unseen_transaction_is_not_dropped_early() {
+    let mut transport = TestTransport::default();
+    for _ in 0..3 {
+        // no receipt and the node has not shown the transaction yet
+        responses(&mut transport, vec![Value::Null, Value::Null]);
+    }
+    responses(&mut transport, vec![json!(receipt(5, 1)), json!("0x5")]);
+    let client = Client::new(transport.clone());
+
+    assert_eq!(client.wait_for_receipt(hash()).await.unwrap(), Outcome::Mined(receipt(5, 1)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn unseen_transaction_is_dropped_after_the_grace_period() {
+    let mut transport = TestTransport::default();
+    // polls at 0, 0.5, 1, 1.5 and 2 seconds
+    for _ in 0..5 {
+        responses(&mut transport, vec![Value::Null, Value::Null]);
+    }
+    let client = Client::new(transport.clone()).with_poll_interval(Duration::from_millis(500));
+
+    let outcome = Waiter::default().unseen_grace(Duration::from_secs(2)).wait(&client, hash()).await.unwrap();
+    assert_eq!(outcome, Outcome::Dropped);
+    for _ in 0..5 {
+        transport.assert_request("eth_getTransactionReceipt", &[format!("\"{:?}\"", hash())]);
+        transport.assert_request("eth_getTransactionByHash", &[format!("\"{:?}\"", hash())]);
+    }
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_at_the_timeout() {
+    let mut transport = TestTransport::default();
+    for _ in 0..3 {
+        responses(&mut transport, vec![Value::Null, pending(0)]);
+    }
+    let client = Client::new(transport.clone()).with_poll_interval(Duration::from_millis(500));
+
+    let outcome = Waiter::default().timeout(Duration::from_secs(1)).wait(&client, hash()).await.unwrap();
+    assert_eq!(outcome, Outcome::TimedOut);
+    assert_eq!(outcome.to_string(), "timed out waiting for the receipt");
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_requests_are_polled_again() {
+    let mut transport = TestTransport::default();
+    // the first receipt lookup and the first block number fail
+    responses(&mut transport, vec![json!(receipt(5, 1)), json!(receipt(5, 1)), json!("0x5")]);
+    let client = Client::new(Flaky { inner: transport.clone(), fails: |id| id == 1 || id == 3 });
+
+    assert_eq!(client.wait_for_receipt(hash()).await.unwrap(), Outcome::Mined(receipt(5, 1)));
+    let receipt_request = [format!("\"{:?}\"", hash())];
+    transport.assert_request("eth_getTransactionReceipt", &receipt_request);
+    transport.assert_request("eth_getTransactionReceipt", &receipt_request);
+    transport.assert_request("eth_blockNumber", &[]);
+    transport.assert_request("eth_getTransactionReceipt", &receipt_request);
+    transport.assert_request("eth_blockNumber", &[]);
+    transport.assert_no_more_requests();
+}
+
+#[tokio::test(start_paused = true)]
+async fn last_error_is_returned_at_the_timeout() {
+    let client = Client::new(Flaky { inner: TestTransport::default(), fails: |_| true }).with_poll_interval(Duration::from_millis(500));
+
+    match Waiter::default().timeout(Duration::from_secs(1)).wait(&client, hash()).await {
+        Err(Error::Rpc(web3::Error::Rpc(e))) => assert_eq!(e.message, "rate limited"),
+        other => panic!("expected the rpc error, got {:?}", other),
+    }
+}
+
+#[test]
+fn revert_messages_are_decoded() {
+    let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+    data.extend(web3::ethabi::encode(&[Token::String("value too large".into())]));
+    assert_eq!(decode_revert(&data), Some("value too large".to_string()));
+
+    // custom errors and panics are not Error(string)
+    assert_eq!(decode_revert(&[0x4e, 0x48, 0x7b, 0x71]), None);
+    assert_eq!(decode_revert(&[]), None);
+}
@@ -1,10 +1,9 @@
 mod past;
+#[allow(dead_code)] // run by swapping the call in main
 mod event;
 
-fn main()
+fn main() -> eth_client::Result<()>
 {
-    past::main(); // for past all event detail and values 
-    // event::main(); // for listen event and give value 
+    past::main() // for past all event detail and values 
+    // event::main() // for listen event and give value 
 }
-
-
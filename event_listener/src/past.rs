@@ -1,51 +1,21 @@
-use ethers::types::TransactionRequest as EthersTxRequest;
-use ethers::types::transaction::eip2718::TypedTransaction;
+use eth_client::{env, Client, Error};
+use ethers::abi::{Address, RawLog};
 use web3::contract::{Contract, Options};
-use ethers::abi::{Abi, Address, RawLog, Token};
-use ethers::prelude::*;
-use ethers::signers::LocalWallet;
-use web3::transports::WebSocket;
-use web3::types::{FilterBuilder, H160, H256, U256};
-use web3::Web3;
-use dotenv::dotenv;
-use std::env;
+use web3::types::{FilterBuilder, H256, U256};
 
 
 #[tokio::main]
-pub async fn main() -> web3::Result<()> {
-       
-    dotenv().ok(); // Load .env file
+pub async fn main() -> eth_client::Result<()> {
 
-    // Connect to Ethereum node (Infura, Alchemy, or Local Node)
-    let transport = WebSocket::new(&env::var("ETHEREUM_WS_URL").expect("Missing ETHEREUM_WS_URL")).await?;
-    let web3 = Web3::new(transport);
+    // Connect to Ethereum node (Infura, Alchemy, or Local Node), url from .env
+    let client = Client::ws_from_env().await?;
 
 
-    // Send transaction (if balance is sufficient)
-    let sender_wallet: LocalWallet = env::var("SENDER_PRIVATE_KEY").expect("Missing SENDER_PRIVATE_KEY").parse().unwrap();
-    let sender = sender_wallet.address();
-
-    //check balance of Sender
-    let balance_sender: U256 = web3.eth().balance(sender, None).await?;
-    let balance_in_eth_sender = ethers::utils::format_units(balance_sender, "ether").unwrap();
-    println!("Balance Of Sender: {} ETH", balance_in_eth_sender);
-
-    let (gas_price, gas_limit, value) = (web3.eth().gas_price().await?, U256::from(21000), U256::exp10(16));
-
-    if balance_sender < value + (gas_limit * gas_price) 
-    {
-        println!("Insufficient balance for transaction.");
-        return Ok(());
-    }
-
-    // let nonce = web3.eth().transaction_count(sender, None).await?;
-    let chain_id = web3.eth().chain_id().await?.as_u64();
-
         //intreracting with contract
         println!("Interacting with the smart contract...");
 
-        let contract_address: Address = env::var("CONTRACT_ADDRESS").expect("CONTRACT_ADDRESS not found in .env").parse::<H160>().unwrap();
-        let contract = Contract::from_json(web3.eth(), contract_address, include_str!("storage_abi.json").as_bytes()).unwrap();
+        let contract_address = env::address(env::CONTRACT_ADDRESS)?;
+        let contract = Contract::from_json(client.web3().eth(), contract_address, include_str!("storage_abi.json").as_bytes())?;
 
         println!("Past Event Values");
         let past_filter = FilterBuilder::default()
@@ -55,7 +25,7 @@ pub async fn main() -> web3::Result<()> {
         .to_block(web3::types::BlockNumber::Latest)
         .build();
 
-        let past_events: Vec<web3::types::Log> = web3.eth().logs(past_filter).await.unwrap();
+        let past_events: Vec<web3::types::Log> = client.web3().eth().logs(past_filter).await?;
 
         println!("Fetching past events...");
         for log_entry in past_events {
@@ -64,24 +34,30 @@ pub async fn main() -> web3::Result<()> {
                 data: log_entry.data.0,
             };
 
-            if let Ok(decoded_log) = contract.abi().event("NumberUpdatedEvent").unwrap().parse_log(raw_log) {
-                let sender: Address = decoded_log.params[0].value.clone().into_address().unwrap();
-                let tx_hash: H256 = log_entry.transaction_hash.unwrap();
-                let block_number = log_entry.block_number.unwrap();
-
-                // Retrieve value stored at that time
-                let stored_value: U256 = contract.query("retrieve", (), sender, Options::default(), Some(block_number.into())).await.unwrap();
+            // pending logs have no block to read the value at yet
+            let (Some(tx_hash), Some(block_number)) = (log_entry.transaction_hash, log_entry.block_number) else {
+                println!("Skipping pending log");
+                continue;
+            };
 
-                println!("========================================");
-                println!("Past events and that values");
-                println!("Transaction Hash: {:?}", tx_hash);
-                println!("Sender: {:?}", sender);
-                println!("Block Number: {:?}", block_number);
-                println!("Stored Value at that Time: {}", stored_value);
-            }
+            // the filter only matches this event, a log that does not decode is a wrong abi
+            let decoded_log = contract.abi().event("NumberUpdatedEvent")?.parse_log(raw_log)?;
+            let sender: Address = decoded_log.params[0]
+                .value
+                .clone()
+                .into_address()
+                .ok_or_else(|| Error::Contract("NumberUpdatedEvent sender is not an address".to_string()))?;
+
+            // Retrieve value stored at that time
+            let stored_value: U256 = contract.query("retrieve", (), sender, Options::default(), Some(block_number.into())).await?;
+
+            println!("========================================");
+            println!("Past events and that values");
+            println!("Transaction Hash: {:?}", tx_hash);
+            println!("Sender: {:?}", sender);
+            println!("Block Number: {:?}", block_number);
+            println!("Stored Value at that Time: {}", stored_value);
         }
 
     Ok(())
 }
-
-
@@ -0,0 +1,164 @@
+// proofs as solidity calldata, and a verifier contract for the same hashing
+// only sha256 and keccak256 trees fit: nodes must be bytes32 and the hash an evm builtin
+use crate::{hash_pair, Hasher, MerkleTree, MultiProof};
+use ethers::abi::{encode, Token};
+use ethers::types::{Bytes, U256};
+use ethers::utils::id;
+
+pub const VERIFY_SIGNATURE: &str = "verify(bytes32[],bytes32,bytes32)";
+pub const MULTI_VERIFY_SIGNATURE: &str = "multiVerify(bytes32[],uint256[],uint256,bytes32[],bytes32)";
+
+// hex hash to bytes32 token, None if hash is not 32 bytes
+fn bytes32(hash: &str) -> Option<Token> {
+    let bytes = hex::decode(hash).ok()?;
+    if bytes.len() != 32 {
+        return None;
+    }
+    Some(Token::FixedBytes(bytes))
+}
+
+fn bytes32_array(hashes: &[String]) -> Option<Token> {
+    Some(Token::Array(hashes.iter().map(|hash| bytes32(hash)).collect::<Option<Vec<Token>>>()?))
+}
+
+// keep only the sibling of each pair, that is what the contract hash with
+pub fn proof_siblings<H: Hasher>(leaf: &[u8], proof: &[(String, String)]) -> Option<Vec<String>> {
+    let mut hash = H::hash(leaf);
+    let mut siblings = vec![];
+
+    for (left, right) in proof {
+        let sibling = if hash == *left { right } else if hash == *right { left } else { return None };
+        siblings.push(sibling.clone());
+        hash = hash_pair::<H>(left, right);
+    }
+    Some(siblings)
+}
+
+// abi.encode(bytes32[]) of the proof, None if proof is not for leaf or hash is not 32 bytes
+pub fn proof_calldata<H: Hasher>(leaf: &[u8], proof: &[(String, String)]) -> Option<Bytes> {
+    let siblings = proof_siblings::<H>(leaf, proof)?;
+    Some(encode(&[bytes32_array(&siblings)?]).into())
+}
+
+// full transaction data for verify(proof, root, leafHash)
+pub fn verify_calldata<H: Hasher>(leaf: &[u8], proof: &[(String, String)], root: &str) -> Option<Bytes> {
+    let siblings = proof_siblings::<H>(leaf, proof)?;
+    let args = encode(&[bytes32_array(&siblings)?, bytes32(root)?, bytes32(&H::hash(leaf))?]);
+    Some([&id(VERIFY_SIGNATURE)[..], &args].concat().into())
+}
+
+// abi.encode(bytes32[], uint256[], uint256) of proof nodes, indices and leaf count
+pub fn multiproof_calldata(proof: &MultiProof) -> Option<Bytes> {
+    let indices = proof.indices.iter().map(|i| Token::Uint(U256::from(*i))).collect();
+    let args = encode(&[bytes32_array(&proof.proof)?, Token::Array(indices), Token::Uint(U256::from(proof.leaf_count))]);
+    Some(args.into())
+}
+
+// full transaction data for multiVerify(proof, indices, leafCount, leafHashes, root)
+// leaves must be in the same order as proof.indices
+pub fn multi_verify_calldata<H: Hasher>(leaves: &[Vec<u8>], proof: &MultiProof, root: &str) -> Option<Bytes> {
+    let hashes: Vec<String> = leaves.iter().map(|leaf| H::hash(leaf)).collect();
+    if !MerkleTree::<H>::verify_multiproof_hashes(&hashes, proof, root) {
+        return None;
+    }
+    let indices = proof.indices.iter().map(|i| Token::Uint(U256::from(*i))).collect();
+    let args = encode(&[
+        bytes32_array(&proof.proof)?,
+        Token::Array(indices),
+        Token::Uint(U256::from(proof.leaf_count)),
+        bytes32_array(&hashes)?,
+        bytes32(root)?,
+    ]);
+    Some([&id(MULTI_VERIFY_SIGNATURE)[..], &args].concat().into())
+}
+
+// verifier contract for trees built with H, None for blake2b and sha512:
+// their 64 byte nodes do not fit bytes32 and the evm can not compute them
+pub fn solidity_verifier<H: Hasher>() -> Option<String> {
+    match H::NAME {
+        "sha256" | "keccak256" => Some(VERIFIER.replace("{hash}", H::NAME)),
+        _ => None,
+    }
+}
+
+// node = hash(lowercase hex(small) ++ lowercase hex(big)), odd node carry forword.
+// {hash} is the solidity builtin, sha256 or keccak256
+const VERIFIER: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// generated by merkle_tree for {hash} trees
+// leaf hash = {hash}(leaf), node = {hash}(hex(small) ++ hex(big)) with lowercase hex
+contract MerkleVerifier {
+    bytes16 private constant HEX = "0123456789abcdef";
+
+    function toHex(bytes32 value) internal pure returns (bytes memory out) {
+        out = new bytes(64);
+        for (uint256 i = 0; i < 32; i++) {
+            uint8 b = uint8(value[i]);
+            out[2 * i] = HEX[b >> 4];
+            out[2 * i + 1] = HEX[b & 0x0f];
+        }
+    }
+
+    function hashPair(bytes32 a, bytes32 b) internal pure returns (bytes32) {
+        return a < b ? {hash}(abi.encodePacked(toHex(a), toHex(b))) : {hash}(abi.encodePacked(toHex(b), toHex(a)));
+    }
+
+    // proof is the sibling at each level, leaf is {hash} of the leaf data
+    function verify(bytes32[] calldata proof, bytes32 root, bytes32 leaf) external pure returns (bool) {
+        bytes32 hash = leaf;
+        for (uint256 i = 0; i < proof.length; i++) {
+            hash = hashPair(hash, proof[i]);
+        }
+        return hash == root;
+    }
+
+    // indices strictly increasing, leaves are the leaf hashes in the same order
+    function multiVerify(
+        bytes32[] calldata proof,
+        uint256[] calldata indices,
+        uint256 leafCount,
+        bytes32[] calldata leaves,
+        bytes32 root
+    ) external pure returns (bool) {
+        uint256 n = indices.length;
+        if (n == 0 || n != leaves.length || indices[n - 1] >= leafCount) {
+            return false;
+        }
+        uint256[] memory idx = new uint256[](n);
+        bytes32[] memory hashes = new bytes32[](n);
+        for (uint256 i = 0; i < n; i++) {
+            if (i > 0 && indices[i - 1] >= indices[i]) {
+                return false;
+            }
+            idx[i] = indices[i];
+            hashes[i] = leaves[i];
+        }
+
+        uint256 size = leafCount;
+        uint256 p = 0;
+        while (size > 1) {
+            uint256 m = 0;
+            for (uint256 i = 0; i < n; i++) {
+                uint256 j = idx[i];
+                bytes32 hash = hashes[i];
+                if (j % 2 == 0 && i + 1 < n && idx[i + 1] == j + 1) {
+                    hash = hashPair(hash, hashes[i + 1]);
+                    i++;
+                } else if ((j ^ 1) < size) {
+                    if (p == proof.length) {
+                        return false;
+                    }
+                    hash = hashPair(hash, proof[p++]);
+                }
+                idx[m] = j / 2;
+                hashes[m] = hash;
+                m++;
+            }
+            n = m;
+            size = (size + 1) / 2;
+        }
+        return p == proof.length && hashes[0] == root;
+    }
+}
+"#;
@@ -0,0 +1,97 @@
+use crate::{Hasher, MerkleTree};
+use ethers::signers::{LocalWallet, WalletError};
+use ethers::types::{Address, Signature};
+use ethers::utils::hash_message;
+
+// what a publisher sign: root with the size and hash algorithm of the tree.
+// domain names the log and epoch goes up with every checkpoint, so a signed
+// checkpoint can not be replayed to another log or after a newer one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub domain: String,
+    pub epoch: u64,
+    pub root: String,
+    pub size: usize,
+    pub hasher: String,
+}
+
+// checkpoint with signatures of one or more publishers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedCheckpoint {
+    pub checkpoint: Checkpoint,
+    pub signatures: Vec<Signature>,
+}
+
+impl Checkpoint {
+
+    pub fn new<H: Hasher>(domain: &str, epoch: u64, root: String, size: usize) -> Self {
+        Checkpoint { domain: domain.to_string(), epoch, root, size, hasher: H::NAME.to_string() }
+    }
+
+    pub fn from_tree<H: Hasher>(tree: &MerkleTree<H>, domain: &str, epoch: u64) -> Self {
+        Self::new::<H>(domain, epoch, tree.get_root(), tree.leaves.len())
+    }
+
+    // text that is signed, EIP-191 prefix is added by sign and recover
+    pub fn message(&self) -> String {
+        format!(
+            "merkle_tree checkpoint\ndomain: {}\nepoch: {}\nhasher: {}\nsize: {}\nroot: 0x{}",
+            self.domain, self.epoch, self.hasher, self.size, self.root
+        )
+    }
+
+    // EIP-191 personal_sign of message()
+    pub fn sign(&self, wallet: &LocalWallet) -> Result<Signature, WalletError> {
+        wallet.sign_hash(hash_message(self.message()))
+    }
+
+    // address that made the signature, None if signature is invalid
+    pub fn signer(&self, signature: &Signature) -> Option<Address> {
+        signature.recover(self.message()).ok()
+    }
+
+    pub fn signed_by(self, wallets: &[LocalWallet]) -> Result<SignedCheckpoint, WalletError> {
+        let signatures = wallets.iter().map(|wallet| self.sign(wallet)).collect::<Result<Vec<_>, _>>()?;
+        Ok(SignedCheckpoint { checkpoint: self, signatures })
+    }
+}
+
+impl SignedCheckpoint {
+
+    pub fn add_signature(&mut self, wallet: &LocalWallet) -> Result<(), WalletError> {
+        self.signatures.push(self.checkpoint.sign(wallet)?);
+        Ok(())
+    }
+
+    // known signers that signed this checkpoint, each counted once
+    pub fn valid_signers(&self, signers: &[Address]) -> Vec<Address> {
+        let mut found: Vec<Address> = vec![];
+        for signature in &self.signatures {
+            if let Some(address) = self.checkpoint.signer(signature) {
+                if signers.contains(&address) && !found.contains(&address) {
+                    found.push(address);
+                }
+            }
+        }
+        found
+    }
+
+    // true if at least threshold (minimum 1) known signers signed
+    pub fn verify(&self, signers: &[Address], threshold: usize) -> bool {
+        self.valid_signers(signers).len() >= threshold.max(1)
+    }
+
+    // what a verifier that last accepted last_epoch for domain should check:
+    // the domain, a newer epoch and the signatures
+    pub fn accept(&self, domain: &str, last_epoch: Option<u64>, signers: &[Address], threshold: usize) -> bool {
+        self.checkpoint.domain == domain
+            && last_epoch.map_or(true, |last| self.checkpoint.epoch > last)
+            && self.verify(signers, threshold)
+    }
+
+    // verify signatures and that checkpoint is for this tree
+    pub fn verify_tree<H: Hasher>(&self, tree: &MerkleTree<H>, signers: &[Address], threshold: usize) -> bool {
+        let checkpoint = &self.checkpoint;
+        *checkpoint == Checkpoint::from_tree(tree, &checkpoint.domain, checkpoint.epoch) && self.verify(signers, threshold)
+    }
+}
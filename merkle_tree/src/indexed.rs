@@ -0,0 +1,187 @@
+use crate::{hash_pair, Hasher};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::marker::PhantomData;
+
+// leaf of indexed tree, value plus pointer to next larger value
+// next_index 0 with empty next_value means there is no larger value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedLeaf {
+    pub value: Vec<u8>,
+    pub next_index: usize,
+    pub next_value: Vec<u8>,
+}
+
+impl IndexedLeaf {
+    // bytes that get hashed for this leaf
+    pub fn encode(&self) -> Vec<u8> {
+        format!("{}:{}:{}", hex::encode(&self.value), self.next_index, hex::encode(&self.next_value)).into_bytes()
+    }
+
+    // true if value is strictly between this leaf and its next leaf
+    pub fn covers(&self, value: &[u8]) -> bool {
+        self.value.as_slice() < value && (self.next_value.is_empty() || value < self.next_value.as_slice())
+    }
+}
+
+// proof that value is not in the tree: the low leaf and its membership proof
+#[derive(Debug, Clone)]
+pub struct NonMembershipProof {
+    pub low_leaf: IndexedLeaf,
+    pub low_index: usize,
+    pub proof: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexedMerkleTree<H: Hasher> {
+    pub leaves: Vec<IndexedLeaf>,
+    pub root: String,
+    // hashes of every level, leaf hashes first and root last
+    levels: Vec<Vec<String>>,
+    // value to leaf index, sorted for the low leaf lookup
+    index: BTreeMap<Vec<u8>, usize>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> Default for IndexedMerkleTree<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Hasher> IndexedMerkleTree<H> {
+
+    // create tree with the zero leaf, every value must be bigger than empty
+    pub fn new() -> Self {
+        let zero = IndexedLeaf { value: vec![], next_index: 0, next_value: vec![] };
+        let levels = vec![vec![H::hash(&zero.encode())]];
+        let root = levels[0][0].clone();
+        IndexedMerkleTree { leaves: vec![zero], root, levels, index: BTreeMap::from([(vec![], 0)]), _hasher: PhantomData }
+    }
+
+    // rehash leaf at index and the nodes above it, same pairing as MerkleTree,
+    // odd node carry forword. a leaf pushed at the end grows the levels
+    fn update_path(&mut self, index: usize) {
+        let hash = H::hash(&self.leaves[index].encode());
+        set(&mut self.levels[0], index, hash);
+
+        let (mut level, mut idx) = (0, index);
+        while self.levels[level].len() > 1 {
+            if self.levels.len() == level + 1 {
+                self.levels.push(vec![]);
+            }
+            let parent = idx / 2;
+            let nodes = &self.levels[level];
+            let hash = match nodes.get(parent * 2 + 1) {
+                Some(right) => hash_pair::<H>(&nodes[parent * 2], right),
+                None => nodes[parent * 2].clone(),
+            };
+            set(&mut self.levels[level + 1], parent, hash);
+            level += 1;
+            idx = parent;
+        }
+        self.root = self.levels[level][0].clone();
+    }
+
+    pub fn get_root(&self) -> String {
+        self.root.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.len() <= 1
+    }
+
+    // index of leaf holding value
+    pub fn find(&self, value: &[u8]) -> Option<usize> {
+        self.index.get(value).copied()
+    }
+
+    // index of leaf with biggest value smaller than given value, None if value is present
+    pub fn low_leaf_index(&self, value: &[u8]) -> Option<usize> {
+        if self.index.contains_key(value) {
+            return None;
+        }
+        self.index.range::<[u8], _>((Bound::Unbounded, Bound::Excluded(value))).next_back().map(|(_, index)| *index)
+    }
+
+    // insert value and return its index, None if it is already present (or empty)
+    pub fn insert(&mut self, value: Vec<u8>) -> Option<usize> {
+        let low_index = self.low_leaf_index(&value)?;
+        let new_index = self.leaves.len();
+
+        //new leaf take over low leaf pointer, low leaf point to new leaf
+        let low = &mut self.leaves[low_index];
+        let new_leaf = IndexedLeaf {
+            value: value.clone(),
+            next_index: low.next_index,
+            next_value: std::mem::replace(&mut low.next_value, value.clone()),
+        };
+        low.next_index = new_index;
+        self.leaves.push(new_leaf);
+        self.index.insert(value, new_index);
+
+        // only the two changed paths get rehashed
+        self.update_path(low_index);
+        self.update_path(new_index);
+        Some(new_index)
+    }
+
+    // membership proof of leaf at index, same format as MerkleTree::get_proof
+    pub fn get_proof(&self, index: usize) -> Option<Vec<(String, String)>> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        let mut proof = vec![];
+        let mut idx = index;
+
+        for nodes in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = nodes.get(idx ^ 1) {
+                let (left, right) = if nodes[idx] < *sibling { (&nodes[idx], sibling) } else { (sibling, &nodes[idx]) };
+                proof.push((left.clone(), right.clone()));
+            }
+            idx /= 2;
+        }
+        Some(proof)
+    }
+
+    // check leaf is in tree with given root, every step must contain current hash
+    pub fn verify_leaf(leaf: &IndexedLeaf, proof: &[(String, String)], root: &str) -> bool {
+        let mut hash = H::hash(&leaf.encode());
+
+        for (left, right) in proof {
+            if hash != *left && hash != *right {
+                return false;
+            }
+            hash = hash_pair::<H>(left, right);
+        }
+        hash == root
+    }
+
+    // proof that value is not in the tree, None if value is present
+    pub fn prove_non_membership(&self, value: &[u8]) -> Option<NonMembershipProof> {
+        let low_index = self.low_leaf_index(value)?;
+        Some(NonMembershipProof {
+            low_leaf: self.leaves[low_index].clone(),
+            low_index,
+            proof: self.get_proof(low_index)?,
+        })
+    }
+
+    // verfies value is missing: low leaf is in tree and value fall in its gap
+    pub fn verify_non_membership(value: &[u8], proof: &NonMembershipProof, root: &str) -> bool {
+        proof.low_leaf.covers(value) && Self::verify_leaf(&proof.low_leaf, &proof.proof, root)
+    }
+}
+
+// overwrite node at index or push it when the level grows by one
+fn set(nodes: &mut Vec<String>, index: usize, hash: String) {
+    if index < nodes.len() {
+        nodes[index] = hash;
+    } else {
+        nodes.push(hash);
+    }
+}
@@ -1,16 +1,30 @@
 use sha2::{Sha256, Sha512, Digest};
-use blake2::{Blake2b512, digest::Update};
-use hex;
+use blake2::Blake2b512;
 use std::marker::PhantomData;
 
+pub mod indexed;
+pub use indexed::{IndexedLeaf, IndexedMerkleTree, NonMembershipProof};
+pub mod stream;
+pub use stream::{SpilledMerkleTree, StreamingMerkleTree};
+pub mod multiproof;
+pub use multiproof::{MultiProof, MultiProofError};
+pub mod abi;
+pub mod checkpoint;
+pub mod pairing;
+pub use pairing::Pairing;
+pub use checkpoint::{Checkpoint, SignedCheckpoint};
+
 // define hasher
 pub trait Hasher {
+    // algorithm name, used in checkpoints and generated verifiers
+    const NAME: &'static str;
     fn hash(data: &[u8]) -> String;
 }
 
 // implement hasher for sha256
 pub struct Sha256Hasher;
 impl Hasher for Sha256Hasher {
+    const NAME: &'static str = "sha256";
     fn hash(data: &[u8]) -> String {
         hex::encode(Sha256::digest(data))
     }
@@ -19,6 +33,7 @@ impl Hasher for Sha256Hasher {
 // implement hasher for blake2
 pub struct Blake2bHasher;
 impl Hasher for Blake2bHasher {
+    const NAME: &'static str = "blake2b";
     fn hash(data: &[u8]) -> String {
         let mut hasher = Blake2b512::new();
         digest::Update::update(&mut hasher, data); 
@@ -29,11 +44,43 @@ impl Hasher for Blake2bHasher {
 // implement hasher for sha512
 pub struct Sha512Hasher;
 impl Hasher for Sha512Hasher {
+    const NAME: &'static str = "sha512";
     fn hash(data: &[u8]) -> String {
         hex::encode(Sha512::digest(data))
     }
 }
 
+// implement hasher for keccak256, the evm's own hash
+pub struct Keccak256Hasher;
+impl Hasher for Keccak256Hasher {
+    const NAME: &'static str = "keccak256";
+    fn hash(data: &[u8]) -> String {
+        hex::encode(ethers::utils::keccak256(data))
+    }
+}
+
+// hash two nodes in sorted order (small left, big right)
+pub(crate) fn hash_pair<H: Hasher>(a: &str, b: &str) -> String {
+    let (left, right) = if a < b { (a, b) } else { (b, a) };
+    H::hash(format!("{}{}", left, right).as_bytes())
+}
+
+// every level from leaf hashes up to the root, odd node carry forword
+pub(crate) fn build_levels<H: Hasher>(leaf_hashes: Vec<String>) -> Vec<Vec<String>> {
+    let mut levels = vec![leaf_hashes];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|chunk| if chunk.len() == 2 { hash_pair::<H>(&chunk[0], &chunk[1]) } else { chunk[0].clone() })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+// leaves are hashed with H and each pair is H(hex(small) + hex(big)) over the hex
+// text, odd node carry forword. the shape is RFC 6962 but the roots are not the
+// ones RFC 6962, bitcoin or openzeppelin publish, use Pairing for those
 #[derive(Debug, Clone)]
 pub struct MerkleTree<H: Hasher> {
     pub leaves: Vec<Vec<u8>>,
@@ -46,60 +93,52 @@ impl<H: Hasher> MerkleTree<H> {
     // create a new markletree from given leaves 
     pub fn new(leaves: Vec<Vec<u8>>) -> Self {
         //make root form given leaves
-        let  root = Self::build_tree(&leaves);
+        let  root = Self::build_tree(&leaves, false);
         // root.push('h');
         //return tree
         MerkleTree { leaves, root, _hasher: PhantomData }
     }
 
-    // build tree and return root
-    fn build_tree(leaves: &Vec<Vec<u8>>) -> String  {
-        
-        //make hash of each leaf
-        let mut hashes: Vec<String> = leaves.iter().map(|leaf| H::hash(leaf)).collect();
+    // print leaf hashes and every level of the tree, new() stays quiet so big trees are not slowed down by stdout
+    pub fn print_levels(&self) {
+        Self::build_tree(&self.leaves, true);
+    }
 
+    // build tree and return root, verbose prints each level
+    fn build_tree(leaves: &[Vec<u8>], verbose: bool) -> String  {
         
-        // print all leaves hash 
-        for (i, hash) in hashes.iter().enumerate() {
-            println!("Leaf {} : {:?}", i+1, hash);
-        }
-        println!("");
-        let mut level = 0;
-        println!("Level {} (Leaves): {:?}\n", level, hashes);
-        
-        //combaining current lavel's
-        while hashes.len() > 1 {
-            level += 1;
-            let mut new_hashes = vec![];
-            let mut level_structure = vec![];
-
-            for chunk in hashes.chunks(2) {
-                let combined_hash = if chunk.len() == 2 {
-                    let (left, right) = if chunk[0] < chunk[1] {
-                        (chunk[0].clone(), chunk[1].clone())
+        //make hash of each leaf and combaine them level by level
+        let levels = build_levels::<H>(leaves.iter().map(|leaf| H::hash(leaf)).collect());
+
+        // print all leaves hash and the pairs of every level
+        if verbose {
+            for (i, hash) in levels[0].iter().enumerate() {
+                println!("Leaf {} : {:?}", i+1, hash);
+            }
+            println!();
+            println!("Level 0 (Leaves): {:?}\n", levels[0]);
+
+            for (level, hashes) in levels[..levels.len() - 1].iter().enumerate() {
+                let level_structure: Vec<(String, String)> = hashes
+                    .chunks(2)
+                    .map(|chunk| if chunk.len() == 2 {
+                        if chunk[0] < chunk[1] { (chunk[0].clone(), chunk[1].clone()) } else { (chunk[1].clone(), chunk[0].clone()) }
                     } else {
-                        (chunk[1].clone(), chunk[0].clone())
-                    };
-                    level_structure.push((left.clone(), right.clone()));
-                    //concatinace hashes
-                    H::hash(format!("{}{}", left, right).as_bytes())
-                } else {
-                    // level_structure.push((chunk[0].clone(), chunk[0].clone()));
-                    level_structure.push((chunk[0].clone(), "carry forword".to_string()));
-                    // if odd then carry forword 
-                    chunk[0].clone() 
-                };
-                
-                new_hashes.push(combined_hash);
+                        // if odd then carry forword
+                        (chunk[0].clone(), "carry forword".to_string())
+                    })
+                    .collect();
+                println!("Level {}: {:?}\n", level + 1, level_structure);
             }
-            println!("Level {}: {:?}\n", level, level_structure);
-            hashes = new_hashes;
         }
-        
 
-        // println!("Merkle Root: {:?}", hashes[0]);
         //signle reaming is root hash
-        hashes[0].clone()
+        levels[levels.len() - 1][0].clone()
+    }
+
+    // all levels from leaf hashes to root, without printing
+    pub(crate) fn levels(&self) -> Vec<Vec<String>> {
+        build_levels::<H>(self.leaves.iter().map(|leaf| H::hash(leaf)).collect())
     }
 
     //return root
@@ -109,41 +148,20 @@ impl<H: Hasher> MerkleTree<H> {
 
     
     //proof of vector of tuple 
-    // boolean indicate that right(True) and left(false)
+    // each step is the (small, big) pair the current hash is part of
     pub fn get_proof(&self, index: usize) -> Vec<(String, String)> {
+        let levels = self.levels();
         let mut proof: Vec<(String, String)> = vec![];
-        //start with bottom lavel hashes 
-        let mut hashes: Vec<String> = self.leaves.iter().map(|leaf| H::hash(leaf)).collect();
         //for current index current lavel
         let mut idx = index;
 
-        //for check all nodes 
-        while hashes.len() > 1 {
-            let mut new_hashes = vec![];
-            
-            //process pair hashes 
-            for i in (0..hashes.len()).step_by(2) {
-                if i + 1 < hashes.len() {
-                    // place small left and big right
-                    let (left, right) = if hashes[i] < hashes[i + 1] {
-                        (hashes[i].clone(), hashes[i + 1].clone())
-                    } else {
-                        (hashes[i + 1].clone(), hashes[i].clone())
-                    };
-                    //compute parent hash
-                    let combined_hash = H::hash(format!("{}{}", left, right).as_bytes());
-
-                    // println!("combined_hash: {:?}", combined_hash);
-
-                    if i == idx || i + 1 == idx {
-                        proof.push((left.clone(), right.clone()));
-                    }
-                    new_hashes.push(combined_hash);
-                } else {
-                    new_hashes.push(hashes[i].clone());
-                }
+        for hashes in &levels[..levels.len() - 1] {
+            // odd node without sibling is carried forword, nothing to prove
+            if let (Some(node), Some(sibling)) = (hashes.get(idx), hashes.get(idx ^ 1)) {
+                // place small left and big right
+                let (left, right) = if node < sibling { (node, sibling) } else { (sibling, node) };
+                proof.push((left.clone(), right.clone()));
             }
-            hashes = new_hashes;
             //update index 
             idx /= 2;
         }
@@ -152,16 +170,21 @@ impl<H: Hasher> MerkleTree<H> {
     }
 
     //verfies a proof against a given root 
+    // every pair must contain the hash computed so far. earlier versions only hashed
+    // the pairs and returned true whenever the last one gave the root, so a proof
+    // for another leaf was accepted for any leaf. such proofs are now rejected
     pub fn verify_proof(leaf: Vec<u8>,proof: Vec<(String, String)>, root: String) -> bool {
         let mut hash = H::hash(&leaf);
 
         for (left, right) in proof {
-            let (small, big) = if left < right { (left, right) } else { (right, left) };
-            hash = H::hash(format!("{}{}", small, big).as_bytes());
+            // current hash must be one side of the pair, else proof is for other leaf
+            if hash != left && hash != right {
+                return false;
+            }
+            hash = hash_pair::<H>(&left, &right);
         }
 
         // validate true if final computed hash matches the root
-        print!("Hash: {:?}",hash);
         hash == root
     }
 
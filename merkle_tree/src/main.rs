@@ -1,7 +1,27 @@
-use std::io;
-use merkle_tree::{MerkleTree, Sha256Hasher, Sha512Hasher,Blake2bHasher};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use merkle_tree::{MerkleTree, StreamingMerkleTree, Sha256Hasher, Sha512Hasher,Blake2bHasher};
 
 fn main() {
+    // merkle_tree <leaves file> [1|2|3] [lines|delimited|<leaf size>] -> stream file and print root
+    // lines are trimmed like typed input, delimited and fixed size leaves are raw bytes
+    if let Some(path) = env::args().nth(1) {
+        let hash_choice = env::args().nth(2).unwrap_or("1".to_string());
+        let format = env::args().nth(3).unwrap_or("lines".to_string());
+        let root = match hash_choice.as_str() {
+            "1" => stream_root::<Sha256Hasher>(&path, &format),
+            "2" => stream_root::<Sha512Hasher>(&path, &format),
+            "3" => stream_root::<Blake2bHasher>(&path, &format),
+            _ => return println!("Invalid choice!"),
+        };
+        match root.unwrap() {
+            Some(root) => println!("Merkle Root: {}", root),
+            None => println!("No leaves in {}", path),
+        }
+        return;
+    }
+
     let mut leaves = Vec::new();
     let mut input = String::new();
 
@@ -24,29 +44,49 @@ fn main() {
 
     match hash_choice {
         1 => {
-            let merkle_tree = MerkleTree::<Sha256Hasher>::new(leaves.clone());
-            process_merkle_tree::<Sha256Hasher>(merkle_tree, &leaves);
+            let merkle_tree = MerkleTree::<Sha256Hasher>::new(leaves);
+            process_merkle_tree::<Sha256Hasher>(merkle_tree);
         }
         2 => {
-            let merkle_tree = MerkleTree::<Sha512Hasher>::new(leaves.clone());
-            process_merkle_tree::<Sha512Hasher>(merkle_tree, &leaves);
+            let merkle_tree = MerkleTree::<Sha512Hasher>::new(leaves);
+            process_merkle_tree::<Sha512Hasher>(merkle_tree);
         }
         3 => {
-            let merkle_tree = MerkleTree::<Blake2bHasher>::new(leaves.clone());
-            process_merkle_tree::<Blake2bHasher>(merkle_tree, &leaves);
+            let merkle_tree = MerkleTree::<Blake2bHasher>::new(leaves);
+            process_merkle_tree::<Blake2bHasher>(merkle_tree);
         }
         _ => println!("Invalid choice!"),
     }
 
-    fn process_merkle_tree<H: merkle_tree::Hasher>(merkle_tree: MerkleTree<H>, leaves: &Vec<Vec<u8>>) {
+    fn process_merkle_tree<H: merkle_tree::Hasher>(merkle_tree: MerkleTree<H>) {
+        merkle_tree.print_levels();
         println!("\nMerkle Root: {}", merkle_tree.get_root());
     
-        for i in 0..leaves.len() {
+        for (i, leaf) in merkle_tree.leaves.iter().enumerate() {
             let proof = merkle_tree.get_proof(i);
             println!("\nProof for LEAF {}: {:?}", i + 1, proof);
     
-            let is_valid = MerkleTree::<H>::verify_proof(leaves[i].clone(), proof, merkle_tree.get_root());
+            let is_valid = MerkleTree::<H>::verify_proof(leaf.clone(), proof, merkle_tree.get_root());
             println!("\nProof Verification: {}\n", is_valid);
         }
     }
 }
+
+// root of a file bigger than memory, only frontier is kept
+fn stream_root<H: merkle_tree::Hasher>(path: &str, format: &str) -> io::Result<Option<String>> {
+    let mut tree = StreamingMerkleTree::<H>::new();
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        "lines" => {
+            for line in reader.lines() {
+                tree.push(line?.trim().as_bytes())?;
+            }
+        }
+        "delimited" => tree.push_delimited(reader)?,
+        size => match size.parse() {
+            Ok(size) => tree.push_fixed(reader, size)?,
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown leaf format {}", size))),
+        },
+    }
+    tree.finish()
+}
@@ -0,0 +1,114 @@
+use crate::{hash_pair, Hasher, MerkleTree};
+use std::fmt;
+
+// proof for many leaves at once, siblings shared by two proven leaves are left out
+// proof nodes are in the order verify_multiproof (and the solidity verifier) use them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    pub indices: Vec<usize>,
+    pub leaf_count: usize,
+    pub proof: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiProofError {
+    NoIndices,
+    OutOfRange { index: usize, leaf_count: usize },
+}
+
+impl fmt::Display for MultiProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultiProofError::NoIndices => write!(f, "no leaf indices given"),
+            MultiProofError::OutOfRange { index, leaf_count } => write!(f, "leaf index {} out of range, tree has {} leaves", index, leaf_count),
+        }
+    }
+}
+
+impl std::error::Error for MultiProofError {}
+
+impl<H: Hasher> MerkleTree<H> {
+
+    // multiproof for leaves at given indices (sorted and deduplicated)
+    pub fn get_multiproof(&self, indices: &[usize]) -> Result<MultiProof, MultiProofError> {
+        let mut known: Vec<usize> = indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        match known.last() {
+            None => return Err(MultiProofError::NoIndices),
+            Some(&index) if index >= self.leaves.len() => {
+                return Err(MultiProofError::OutOfRange { index, leaf_count: self.leaves.len() })
+            }
+            _ => {}
+        }
+        let result = known.clone();
+        let mut proof = vec![];
+
+        for level in self.levels() {
+            if level.len() == 1 {
+                break;
+            }
+            let mut next = vec![];
+            let mut i = 0;
+            while i < known.len() {
+                let j = known[i];
+                if j % 2 == 0 && known.get(i + 1) == Some(&(j + 1)) {
+                    // both children known, nothing to prove
+                    i += 1;
+                } else if j ^ 1 < level.len() {
+                    proof.push(level[j ^ 1].clone());
+                }
+                next.push(j / 2);
+                i += 1;
+            }
+            known = next;
+        }
+
+        Ok(MultiProof { indices: result, leaf_count: self.leaves.len(), proof })
+    }
+
+    // leaves must be in the same order as proof.indices
+    pub fn verify_multiproof(leaves: &[Vec<u8>], proof: &MultiProof, root: &str) -> bool {
+        let hashes = leaves.iter().map(|leaf| H::hash(leaf)).collect::<Vec<String>>();
+        Self::verify_multiproof_hashes(&hashes, proof, root)
+    }
+
+    // same as verify_multiproof but with leaf hashes, step for step like the solidity verifier
+    pub fn verify_multiproof_hashes(hashes: &[String], proof: &MultiProof, root: &str) -> bool {
+        if hashes.is_empty() || hashes.len() != proof.indices.len() {
+            return false;
+        }
+        if proof.indices.windows(2).any(|w| w[0] >= w[1]) || proof.indices[proof.indices.len() - 1] >= proof.leaf_count {
+            return false;
+        }
+
+        let mut known: Vec<(usize, String)> = proof.indices.iter().copied().zip(hashes.iter().cloned()).collect();
+        let mut siblings = proof.proof.iter();
+        let mut size = proof.leaf_count;
+
+        while size > 1 {
+            let mut next = vec![];
+            let mut i = 0;
+            while i < known.len() {
+                let (j, hash) = &known[i];
+                let parent = if j % 2 == 0 && known.get(i + 1).map(|k| k.0) == Some(j + 1) {
+                    i += 1;
+                    hash_pair::<H>(hash, &known[i].1)
+                } else if j ^ 1 < size {
+                    match siblings.next() {
+                        Some(sibling) => hash_pair::<H>(hash, sibling),
+                        None => return false,
+                    }
+                } else {
+                    hash.clone()
+                };
+                next.push((j / 2, parent));
+                i += 1;
+            }
+            known = next;
+            size = size.div_ceil(2);
+        }
+
+        siblings.next().is_none() && known.len() == 1 && known[0].1 == root
+    }
+}
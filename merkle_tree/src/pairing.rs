@@ -0,0 +1,105 @@
+// merkle trees the way other systems build them, over raw 32 byte hashes instead
+// of the hex strings MerkleTree pairs. roots match what those systems publish:
+//   Rfc6962      certificate transparency, sha256 with 0x00 leaf and 0x01 node prefix,
+//                odd node carry forword
+//   Bitcoin      block merkle root, double sha256 of txids, odd node paired with itself
+//   OpenZeppelin MerkleProof.sol, keccak256 of the sorted pair, odd node carry forword
+use ethers::utils::keccak256;
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pairing {
+    Rfc6962,
+    Bitcoin,
+    OpenZeppelin,
+}
+
+fn sha256(parts: &[&[u8]]) -> Hash {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+impl Pairing {
+    pub fn hash_leaf(&self, leaf: &[u8]) -> Hash {
+        match self {
+            Pairing::Rfc6962 => sha256(&[&[0x00], leaf]),
+            Pairing::Bitcoin => sha256(&[&sha256(&[leaf])]),
+            Pairing::OpenZeppelin => keccak256(leaf),
+        }
+    }
+
+    pub fn hash_node(&self, left: &Hash, right: &Hash) -> Hash {
+        match self {
+            Pairing::Rfc6962 => sha256(&[&[0x01], left, right]),
+            Pairing::Bitcoin => sha256(&[&sha256(&[left, right])]),
+            Pairing::OpenZeppelin => {
+                let (a, b) = if left <= right { (left, right) } else { (right, left) };
+                keccak256([&a[..], &b[..]].concat())
+            }
+        }
+    }
+
+    // node for an odd one out at the end of a level, None means carry forword
+    fn odd(&self, node: &Hash) -> Option<Hash> {
+        match self {
+            Pairing::Bitcoin => Some(self.hash_node(node, node)),
+            _ => None,
+        }
+    }
+
+    fn next_level(&self, hashes: &[Hash]) -> Vec<Hash> {
+        hashes
+            .chunks(2)
+            .map(|chunk| match chunk {
+                [left, right] => self.hash_node(left, right),
+                [node] => self.odd(node).unwrap_or(*node),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    // root of raw leaves, None if there are none
+    pub fn root(&self, leaves: &[Vec<u8>]) -> Option<Hash> {
+        self.root_of_hashes(leaves.iter().map(|leaf| self.hash_leaf(leaf)).collect())
+    }
+
+    // root over leaves that already are hashes, like bitcoin txids
+    pub fn root_of_hashes(&self, mut hashes: Vec<Hash>) -> Option<Hash> {
+        while hashes.len() > 1 {
+            hashes = self.next_level(&hashes);
+        }
+        hashes.first().copied()
+    }
+
+    // siblings from leaf to root, true when the sibling is on the left
+    pub fn proof(&self, mut hashes: Vec<Hash>, index: usize) -> Option<Vec<(Hash, bool)>> {
+        if index >= hashes.len() {
+            return None;
+        }
+        let mut proof = vec![];
+        let mut idx = index;
+        while hashes.len() > 1 {
+            let sibling = idx ^ 1;
+            if sibling < hashes.len() {
+                proof.push((hashes[sibling], sibling < idx));
+            } else if self.odd(&hashes[idx]).is_some() {
+                proof.push((hashes[idx], false));
+            }
+            hashes = self.next_level(&hashes);
+            idx /= 2;
+        }
+        Some(proof)
+    }
+
+    pub fn verify(&self, leaf_hash: Hash, proof: &[(Hash, bool)], root: &Hash) -> bool {
+        let hash = proof.iter().fold(leaf_hash, |hash, (sibling, left)| {
+            if *left { self.hash_node(sibling, &hash) } else { self.hash_node(&hash, sibling) }
+        });
+        hash == *root
+    }
+}
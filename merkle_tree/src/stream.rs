@@ -0,0 +1,243 @@
+use crate::{hash_pair, Hasher};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+// level file name inside spill dir, one hex hash per line
+fn level_path(dir: &Path, level: usize) -> PathBuf {
+    dir.join(format!("level_{}.txt", level))
+}
+
+// longest leaf push_delimited accepts
+pub const MAX_LEAF_LEN: usize = 16 * 1024 * 1024;
+
+// fill buf, false on a clean end before the first byte, error on a cut off leaf
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "input ends inside a leaf")),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+// build root without keeping leaves in memory
+// frontier[k] is the pending node of a full subtree with 2^k leaves
+pub struct StreamingMerkleTree<H: Hasher> {
+    frontier: Vec<Option<String>>,
+    len: usize,
+    spill: Option<(PathBuf, Vec<BufWriter<File>>)>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> Default for StreamingMerkleTree<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Hasher> StreamingMerkleTree<H> {
+
+    // only compute root
+    pub fn new() -> Self {
+        StreamingMerkleTree { frontier: vec![], len: 0, spill: None, _hasher: PhantomData }
+    }
+
+    // also write every level to dir so proofs can be made later with SpilledMerkleTree
+    pub fn with_spill(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        // remove levels of old run so open() don't read them
+        let mut level = 0;
+        while level_path(&dir, level).exists() {
+            fs::remove_file(level_path(&dir, level))?;
+            level += 1;
+        }
+        Ok(StreamingMerkleTree { frontier: vec![], len: 0, spill: Some((dir, vec![])), _hasher: PhantomData })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn write(&mut self, level: usize, hash: &str) -> io::Result<()> {
+        if let Some((dir, writers)) = &mut self.spill {
+            while writers.len() <= level {
+                writers.push(BufWriter::new(File::create(level_path(dir, writers.len()))?));
+            }
+            writeln!(writers[level], "{}", hash)?;
+        }
+        Ok(())
+    }
+
+    // add one leaf, merge full subtrees like binary counter
+    pub fn push(&mut self, leaf: &[u8]) -> io::Result<()> {
+        let mut hash = H::hash(leaf);
+        let mut level = 0;
+        self.write(level, &hash)?;
+
+        while let Some(left) = self.frontier.get_mut(level).and_then(Option::take) {
+            hash = hash_pair::<H>(&left, &hash);
+            level += 1;
+            self.write(level, &hash)?;
+        }
+        if self.frontier.len() <= level {
+            self.frontier.resize(level + 1, None);
+        }
+        self.frontier[level] = Some(hash);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn extend<I, L>(&mut self, leaves: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = L>,
+        L: AsRef<[u8]>,
+    {
+        for leaf in leaves {
+            self.push(leaf.as_ref())?;
+        }
+        Ok(())
+    }
+
+    // leaves as 4 byte big endian length then the bytes, taken as they are so
+    // binary data works. a length over MAX_LEAF_LEN is an error, not an allocation
+    pub fn push_delimited<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
+        let mut len = [0u8; 4];
+        let mut leaf = vec![];
+        while read_full(&mut reader, &mut len)? {
+            let len = u32::from_be_bytes(len) as usize;
+            if len > MAX_LEAF_LEN {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("leaf of {} bytes, limit is {}", len, MAX_LEAF_LEN)));
+            }
+            leaf.resize(len, 0);
+            reader.read_exact(&mut leaf)?;
+            self.push(&leaf)?;
+        }
+        Ok(())
+    }
+
+    // leaves of size bytes back to back, like a file of 32 byte hashes
+    pub fn push_fixed<R: Read>(&mut self, mut reader: R, size: usize) -> io::Result<()> {
+        if size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "leaf size must not be 0"));
+        }
+        let mut leaf = vec![0u8; size];
+        while read_full(&mut reader, &mut leaf)? {
+            self.push(&leaf)?;
+        }
+        Ok(())
+    }
+
+    // fold pending nodes from bottom, odd node carry forword like MerkleTree
+    // return root, None if no leaves
+    pub fn finish(mut self) -> io::Result<Option<String>> {
+        let frontier = std::mem::take(&mut self.frontier);
+        let top = frontier.len();
+        let mut carry: Option<String> = None;
+        let mut written = false;
+
+        for (level, node) in frontier.into_iter().enumerate() {
+            written = false;
+            carry = match (node, carry) {
+                (Some(node), None) => {
+                    written = true;
+                    Some(node)
+                }
+                (Some(node), Some(right)) => {
+                    self.write(level, &right)?;
+                    Some(hash_pair::<H>(&node, &right))
+                }
+                (None, Some(right)) => {
+                    self.write(level, &right)?;
+                    Some(right)
+                }
+                (None, None) => None,
+            };
+        }
+
+        if let Some(root) = &carry {
+            if !written {
+                self.write(top, root)?;
+            }
+        }
+        if let Some((_, writers)) = &mut self.spill {
+            for writer in writers {
+                writer.flush()?;
+            }
+        }
+        Ok(carry)
+    }
+}
+
+// tree whose levels are on disk, read only the nodes a proof need
+pub struct SpilledMerkleTree<H: Hasher> {
+    dir: PathBuf,
+    levels: Vec<usize>,
+    width: usize,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> SpilledMerkleTree<H> {
+
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let width = H::hash(b"").len() + 1;
+        let mut levels = vec![];
+        while let Ok(meta) = fs::metadata(level_path(&dir, levels.len())) {
+            levels.push(meta.len() as usize / width);
+        }
+        if levels.last() != Some(&1) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "spill dir has no complete tree"));
+        }
+        Ok(SpilledMerkleTree { dir, levels, width, _hasher: PhantomData })
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels[0] == 0
+    }
+
+    fn read(&self, file: &mut File, index: usize) -> io::Result<String> {
+        let mut buf = vec![0u8; self.width - 1];
+        file.seek(SeekFrom::Start((index * self.width) as u64))?;
+        file.read_exact(&mut buf)?;
+        String::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn get_root(&self) -> io::Result<String> {
+        let mut file = File::open(level_path(&self.dir, self.levels.len() - 1))?;
+        self.read(&mut file, 0)
+    }
+
+    // same proof as MerkleTree::get_proof for the same leaves
+    pub fn get_proof(&self, index: usize) -> io::Result<Vec<(String, String)>> {
+        let mut proof = vec![];
+        let mut idx = index;
+
+        for level in 0..self.levels.len() - 1 {
+            let sibling = idx ^ 1;
+            if sibling < self.levels[level] {
+                let mut file = File::open(level_path(&self.dir, level))?;
+                let node = self.read(&mut file, idx)?;
+                let other = self.read(&mut file, sibling)?;
+                proof.push(if node < other { (node, other) } else { (other, node) });
+            }
+            idx /= 2;
+        }
+        Ok(proof)
+    }
+}
@@ -0,0 +1,49 @@
+// checkpoints signed by a set of known keys, threshold and replay checks
+use ethers::signers::{LocalWallet, Signer};
+use merkle_tree::{Blake2bHasher, Checkpoint, MerkleTree, Sha256Hasher};
+
+fn leaves(n: usize) -> Vec<Vec<u8>> {
+    (0..n).map(|i| format!("leaf{}", i).into_bytes()).collect()
+}
+
+#[test]
+fn checkpoint_threshold() {
+    let wallets: Vec<LocalWallet> = (1..=4).map(|i| format!("{:064x}", i).parse().unwrap()).collect();
+    let known: Vec<_> = wallets[..3].iter().map(|wallet| wallet.address()).collect();
+    let tree = MerkleTree::<Sha256Hasher>::new(leaves(5));
+
+    let checkpoint = Checkpoint::from_tree(&tree, "test-log", 7);
+    assert_eq!(checkpoint.hasher, "sha256");
+    assert_eq!(checkpoint.size, 5);
+    assert_eq!(checkpoint.signer(&checkpoint.sign(&wallets[0]).unwrap()), Some(known[0]));
+
+    // one known signer twice and one unknown signer count as one
+    let mut signed = checkpoint.clone().signed_by(&[wallets[0].clone(), wallets[0].clone(), wallets[3].clone()]).unwrap();
+    assert!(signed.verify(&known, 1));
+    assert!(!signed.verify(&known, 2));
+
+    signed.add_signature(&wallets[2]).unwrap();
+    assert!(signed.verify(&known, 2));
+    assert!(signed.verify_tree(&tree, &known, 2));
+    assert!(!signed.verify_tree(&MerkleTree::<Sha256Hasher>::new(leaves(6)), &known, 2));
+
+    // signatures do not carry over to another root or hasher
+    // replay protection: same domain, newer epoch, and the signature covers both
+    assert!(signed.accept("test-log", None, &known, 2));
+    assert!(signed.accept("test-log", Some(6), &known, 2));
+    assert!(!signed.accept("test-log", Some(7), &known, 2));
+    assert!(!signed.accept("other-log", Some(6), &known, 2));
+    let mut replayed = signed.clone();
+    replayed.checkpoint.epoch = 8;
+    assert!(!replayed.accept("test-log", Some(7), &known, 1));
+    let mut moved = signed.clone();
+    moved.checkpoint.domain = "other-log".to_string();
+    assert!(!moved.accept("other-log", None, &known, 1));
+
+    let mut forged = signed.clone();
+    forged.checkpoint.root = MerkleTree::<Sha256Hasher>::new(leaves(6)).get_root();
+    assert!(!forged.verify(&known, 1));
+    let mut forged = signed;
+    forged.checkpoint = Checkpoint::new::<Blake2bHasher>("test-log", 7, forged.checkpoint.root, 5);
+    assert!(!forged.verify(&known, 1));
+}
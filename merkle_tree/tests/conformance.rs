@@ -0,0 +1,264 @@
+// conformance suite: published hash vectors, published RFC 6962, bitcoin and
+// openzeppelin roots through the pairing modes, and property tests for every hasher
+//
+// MerkleTree pairs hex strings (hex(small) + hex(big)), a scheme of its own, so its
+// roots are not the ones RFC 6962, bitcoin or openzeppelin publish. external roots are
+// checked through Pairing, which hashes raw bytes the way those systems do, and
+// MerkleTree is checked against the RFC 6962 tree shape: odd node carry forword give
+// the same split and the same audit path length
+
+use ethers::abi::{encode, Token};
+use ethers::types::U256;
+use ethers::utils::keccak256;
+use merkle_tree::pairing::Hash;
+use merkle_tree::{Blake2bHasher, Hasher, Keccak256Hasher, MerkleTree, Pairing, Sha256Hasher, Sha512Hasher};
+use proptest::prelude::*;
+
+fn leaves(n: usize) -> Vec<Vec<u8>> {
+    (0..n).map(|i| format!("leaf{}", i).into_bytes()).collect()
+}
+
+fn node<H: Hasher>(a: &str, b: &str) -> String {
+    let (left, right) = if a < b { (a, b) } else { (b, a) };
+    H::hash(format!("{}{}", left, right).as_bytes())
+}
+
+// RFC 6962 section 2.1 MTH with the crate's node hash
+fn rfc6962_root<H: Hasher>(hashes: &[String]) -> String {
+    if hashes.len() == 1 {
+        return hashes[0].clone();
+    }
+    let k = split(hashes.len());
+    node::<H>(&rfc6962_root::<H>(&hashes[..k]), &rfc6962_root::<H>(&hashes[k..]))
+}
+
+// RFC 6962 section 2.1.1 PATH(m, D[n]) length
+fn rfc6962_path_len(m: usize, n: usize) -> usize {
+    if n == 1 {
+        return 0;
+    }
+    let k = split(n);
+    if m < k { 1 + rfc6962_path_len(m, k) } else { 1 + rfc6962_path_len(m - k, n - k) }
+}
+
+// largest power of two smaller than n
+fn split(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+// FIPS 180-2 appendix B and RFC 7693 appendix A, message "abc". keccak256 is
+// the original keccak the evm uses, not FIPS 202 sha3-256
+#[test]
+fn hashers_match_published_vectors() {
+    assert_eq!(Sha256Hasher::hash(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(Sha256Hasher::hash(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    assert_eq!(Keccak256Hasher::hash(b"abc"), "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45");
+    assert_eq!(Keccak256Hasher::hash(b""), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+    assert_eq!(
+        Sha512Hasher::hash(b"abc"),
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+    );
+    assert_eq!(
+        Blake2bHasher::hash(b"abc"),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+}
+
+fn check_rfc6962_shape<H: Hasher>() {
+    for n in 1..=33 {
+        let tree = MerkleTree::<H>::new(leaves(n));
+        let hashes: Vec<String> = tree.leaves.iter().map(|leaf| H::hash(leaf)).collect();
+        assert_eq!(tree.get_root(), rfc6962_root::<H>(&hashes), "root n={}", n);
+        for m in 0..n {
+            assert_eq!(tree.get_proof(m).len(), rfc6962_path_len(m, n), "path n={} m={}", n, m);
+        }
+    }
+}
+
+#[test]
+fn tree_shape_matches_rfc6962() {
+    check_rfc6962_shape::<Sha256Hasher>();
+    check_rfc6962_shape::<Sha512Hasher>();
+    check_rfc6962_shape::<Blake2bHasher>();
+}
+
+// certificate transparency reference tests (merkle_tree_test.cc), roots of the
+// first n of these leaves
+const RFC6962_LEAVES: [&str; 8] = ["", "00", "10", "2021", "3031", "40414243", "5051525354555657", "606162636465666768696a6b6c6d6e6f"];
+const RFC6962_ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+#[test]
+fn rfc6962_published_roots() {
+    let leaves: Vec<Vec<u8>> = RFC6962_LEAVES.iter().map(|input| hex::decode(input).unwrap()).collect();
+    for (n, root) in RFC6962_ROOTS.iter().enumerate() {
+        assert_eq!(hex::encode(Pairing::Rfc6962.root(&leaves[..=n]).unwrap()), *root, "n={}", n + 1);
+    }
+}
+
+// MerkleTree has no 0x00/0x01 prefixes and hashes hex text, so the same leaves
+// give other roots. a match here would mean the docs on MerkleTree are wrong
+#[test]
+fn merkle_tree_roots_are_not_rfc6962() {
+    let leaves: Vec<Vec<u8>> = RFC6962_LEAVES.iter().map(|input| hex::decode(input).unwrap()).collect();
+    for (n, root) in RFC6962_ROOTS.iter().enumerate() {
+        assert_ne!(MerkleTree::<Sha256Hasher>::new(leaves[..=n].to_vec()).get_root(), *root, "n={}", n + 1);
+    }
+}
+
+// txids and roots are shown byte reversed, like block explorers do
+fn txid(display: &str) -> Hash {
+    let mut hash: Hash = hex::decode(display).unwrap().try_into().unwrap();
+    hash.reverse();
+    hash
+}
+
+// blocks 170 and 100000 from the bitcoin main chain
+#[test]
+fn bitcoin_published_roots() {
+    let block_170 = vec![
+        txid("b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082"),
+        txid("f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16"),
+    ];
+    assert_eq!(
+        Pairing::Bitcoin.root_of_hashes(block_170).unwrap(),
+        txid("7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff")
+    );
+
+    let block_100000 = vec![
+        txid("8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87"),
+        txid("fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4"),
+        txid("6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4"),
+        txid("e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d"),
+    ];
+    let root = txid("f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766");
+    assert_eq!(Pairing::Bitcoin.root_of_hashes(block_100000.clone()).unwrap(), root);
+
+    // odd levels pair the last node with itself, so a duplicated last txid gives
+    // the same root (CVE-2012-2459)
+    let three = block_100000[..3].to_vec();
+    let mut padded = three.clone();
+    padded.push(three[2]);
+    assert_eq!(Pairing::Bitcoin.root_of_hashes(three), Pairing::Bitcoin.root_of_hashes(padded));
+}
+
+// the StandardMerkleTree example from the @openzeppelin/merkle-tree readme. its
+// leaves are keccak256(keccak256(abi.encode(address, uint256)))
+#[test]
+fn openzeppelin_published_root() {
+    let leaf = |address: &str, amount: &str| {
+        keccak256(encode(&[Token::Address(address.parse().unwrap()), Token::Uint(U256::from_dec_str(amount).unwrap())])).to_vec()
+    };
+    let leaves = vec![
+        leaf("0x1111111111111111111111111111111111111111", "5000000000000000000"),
+        leaf("0x2222222222222222222222222222222222222222", "2500000000000000000"),
+    ];
+    let root = "d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77";
+    assert_eq!(hex::encode(Pairing::OpenZeppelin.root(&leaves).unwrap()), root);
+
+    // MerkleTree hashes the leaves again and pairs hex text, not the raw 32 bytes
+    assert_ne!(MerkleTree::<Keccak256Hasher>::new(leaves).get_root(), root);
+}
+
+fn check_pairing(pairing: Pairing, leaves: Vec<Vec<u8>>, pick: usize) {
+    let hashes: Vec<Hash> = leaves.iter().map(|leaf| pairing.hash_leaf(leaf)).collect();
+    let root = pairing.root(&leaves).unwrap();
+    for (i, hash) in hashes.iter().enumerate() {
+        assert!(pairing.verify(*hash, &pairing.proof(hashes.clone(), i).unwrap(), &root));
+    }
+    assert!(pairing.proof(hashes.clone(), hashes.len()).is_none());
+
+    let index = pick % hashes.len();
+    let mut other = hashes[index];
+    other[0] ^= 1;
+    if !hashes.contains(&other) {
+        assert!(!pairing.verify(other, &pairing.proof(hashes, index).unwrap(), &root));
+    }
+}
+
+// flip one hex char so value stay valid hex
+fn mutate(hash: &str, at: usize) -> String {
+    let mut chars: Vec<char> = hash.chars().collect();
+    let i = at % chars.len();
+    chars[i] = if chars[i] == '0' { '1' } else { '0' };
+    chars.into_iter().collect()
+}
+
+fn check_proofs<H: Hasher>(leaves: Vec<Vec<u8>>, pick: usize, step: usize, at: usize, right: bool) {
+    let tree = MerkleTree::<H>::new(leaves.clone());
+    let root = tree.get_root();
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        assert!(MerkleTree::<H>::verify_proof(leaf.clone(), tree.get_proof(i), root.clone()));
+    }
+
+    let index = pick % leaves.len();
+    let proof = tree.get_proof(index);
+
+    // leaf that is not in the tree
+    let mut other = leaves[index].clone();
+    other.push(0xff);
+    if !leaves.contains(&other) {
+        assert!(!MerkleTree::<H>::verify_proof(other, proof.clone(), root.clone()));
+    }
+
+    if proof.is_empty() {
+        return;
+    }
+    let step = step % proof.len();
+
+    let mut mutated = proof.clone();
+    if right {
+        mutated[step].1 = mutate(&mutated[step].1, at);
+    } else {
+        mutated[step].0 = mutate(&mutated[step].0, at);
+    }
+    assert!(!MerkleTree::<H>::verify_proof(leaves[index].clone(), mutated, root.clone()));
+
+    let mut dropped = proof;
+    dropped.remove(step);
+    assert!(!MerkleTree::<H>::verify_proof(leaves[index].clone(), dropped, root));
+}
+
+// distinct leaves, equal leaves give equal subtrees and proofs that fit many paths
+fn leaf_set() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    prop::collection::btree_set(prop::collection::vec(any::<u8>(), 0..16), 1..24)
+        .prop_map(|set| set.into_iter().collect::<Vec<_>>())
+        .prop_shuffle()
+}
+
+proptest! {
+    #[test]
+    fn sha256_proofs(leaves in leaf_set(), pick: usize, step: usize, at: usize, right: bool) {
+        check_proofs::<Sha256Hasher>(leaves, pick, step, at, right);
+    }
+
+    #[test]
+    fn sha512_proofs(leaves in leaf_set(), pick: usize, step: usize, at: usize, right: bool) {
+        check_proofs::<Sha512Hasher>(leaves, pick, step, at, right);
+    }
+
+    #[test]
+    fn blake2b_proofs(leaves in leaf_set(), pick: usize, step: usize, at: usize, right: bool) {
+        check_proofs::<Blake2bHasher>(leaves, pick, step, at, right);
+    }
+
+    #[test]
+    fn pairing_proofs(leaves in leaf_set(), pick: usize) {
+        check_pairing(Pairing::Rfc6962, leaves.clone(), pick);
+        check_pairing(Pairing::Bitcoin, leaves.clone(), pick);
+        check_pairing(Pairing::OpenZeppelin, leaves, pick);
+    }
+}
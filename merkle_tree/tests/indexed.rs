@@ -0,0 +1,102 @@
+// indexed tree updates only the changed paths, so check it against a tree
+// built from scratch over the same leaves after every insert
+use merkle_tree::{Blake2bHasher, Hasher, IndexedMerkleTree, MerkleTree, Sha256Hasher, Sha512Hasher};
+use proptest::prelude::*;
+
+type Tree = IndexedMerkleTree<Sha256Hasher>;
+
+fn rebuilt_root(tree: &Tree) -> String {
+    MerkleTree::<Sha256Hasher>::new(tree.leaves.iter().map(|leaf| leaf.encode()).collect()).get_root()
+}
+
+#[test]
+fn incremental_root_matches_rebuild() {
+    let mut tree = Tree::new();
+    assert_eq!(tree.get_root(), rebuilt_root(&tree));
+
+    // out of order so low leaves sit all over the tree
+    for i in 0..40u32 {
+        let value = ((i * 7919) % 101).to_be_bytes().to_vec();
+        assert!(tree.insert(value).is_some());
+        assert_eq!(tree.get_root(), rebuilt_root(&tree), "after {} inserts", i + 1);
+    }
+    for (i, leaf) in tree.leaves.iter().enumerate() {
+        assert!(Tree::verify_leaf(leaf, &tree.get_proof(i).unwrap(), &tree.get_root()));
+    }
+}
+
+#[test]
+fn low_leaf_lookup() {
+    let mut tree = Tree::new();
+    for value in [vec![10], vec![30], vec![20]] {
+        tree.insert(value);
+    }
+    assert_eq!(tree.find(&[20]), Some(3));
+    assert_eq!(tree.find(&[25]), None);
+    assert_eq!(tree.low_leaf_index(&[25]), Some(3));
+    assert_eq!(tree.low_leaf_index(&[5]), Some(0));
+    assert_eq!(tree.low_leaf_index(&[40]), Some(2));
+    assert_eq!(tree.low_leaf_index(&[30]), None);
+    assert_eq!(tree.leaves[3].next_value, vec![30]);
+    assert_eq!(tree.leaves[1].next_value, vec![20]);
+}
+
+#[test]
+fn proof_out_of_range_is_none() {
+    let mut tree = Tree::new();
+    tree.insert(vec![1]);
+    assert!(tree.get_proof(1).is_some());
+    assert!(tree.get_proof(2).is_none());
+}
+
+fn check_indexed<H: Hasher>(values: Vec<Vec<u8>>, missing: Vec<u8>) {
+    let mut tree = IndexedMerkleTree::<H>::new();
+    for value in &values {
+        tree.insert(value.clone());
+    }
+    let root = tree.get_root();
+
+    for (i, leaf) in tree.leaves.iter().enumerate() {
+        assert!(IndexedMerkleTree::<H>::verify_leaf(leaf, &tree.get_proof(i).unwrap(), &root));
+    }
+    for value in &values {
+        assert!(tree.insert(value.clone()).is_none());
+        assert!(tree.prove_non_membership(value).is_none());
+    }
+
+    if missing.is_empty() || values.contains(&missing) {
+        return;
+    }
+    let proof = tree.prove_non_membership(&missing).unwrap();
+    assert!(IndexedMerkleTree::<H>::verify_non_membership(&missing, &proof, &root));
+
+    // low leaf that skip over a present value must not verify
+    let mut forged = proof.clone();
+    forged.low_leaf.next_value = vec![];
+    forged.low_leaf.next_index = 0;
+    if forged.low_leaf != proof.low_leaf {
+        assert!(!IndexedMerkleTree::<H>::verify_non_membership(&missing, &forged, &root));
+    }
+}
+
+fn value_set() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    prop::collection::vec(prop::collection::vec(any::<u8>(), 1..4), 0..24)
+}
+
+
+proptest! {
+    #[test]
+    fn sha256_indexed(values in value_set(), missing in prop::collection::vec(any::<u8>(), 0..4)) {
+        check_indexed::<Sha256Hasher>(values, missing);
+    }
+
+    #[test]
+    fn sha512_indexed(values in value_set(), missing in prop::collection::vec(any::<u8>(), 0..4)) {
+        check_indexed::<Sha512Hasher>(values, missing);
+    }
+
+    #[test]
+    fn blake2b_indexed(values in value_set(), missing in prop::collection::vec(any::<u8>(), 0..4)) {
+        check_indexed::<Blake2bHasher>(values, missing);
+    }
+}
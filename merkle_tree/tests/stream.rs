@@ -0,0 +1,94 @@
+// raw leaf readers of the streaming builder, bytes go in untouched
+use merkle_tree::stream::MAX_LEAF_LEN;
+use merkle_tree::{Blake2bHasher, Hasher, MerkleTree, Sha256Hasher, Sha512Hasher, SpilledMerkleTree, StreamingMerkleTree};
+use proptest::prelude::*;
+use std::io::ErrorKind;
+
+type Stream = StreamingMerkleTree<Sha256Hasher>;
+
+// whitespace, newlines and bytes that are not utf-8
+fn binary_leaves() -> Vec<Vec<u8>> {
+    vec![b" padded \n".to_vec(), vec![0xff, 0xfe, 0x00], vec![], b"\r\n".to_vec(), vec![0x80; 40]]
+}
+
+fn delimited(leaves: &[Vec<u8>]) -> Vec<u8> {
+    leaves.iter().flat_map(|leaf| (leaf.len() as u32).to_be_bytes().into_iter().chain(leaf.iter().copied())).collect()
+}
+
+#[test]
+fn delimited_leaves_are_not_trimmed() {
+    let leaves = binary_leaves();
+    let mut stream = Stream::new();
+    stream.push_delimited(delimited(&leaves).as_slice()).unwrap();
+    assert_eq!(stream.len(), leaves.len());
+    assert_eq!(stream.finish().unwrap(), Some(MerkleTree::<Sha256Hasher>::new(leaves).get_root()));
+}
+
+#[test]
+fn fixed_size_leaves() {
+    let leaves: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i, b'\n', b' ', 0xff]).collect();
+    let mut stream = Stream::new();
+    stream.push_fixed(leaves.concat().as_slice(), 4).unwrap();
+    assert_eq!(stream.finish().unwrap(), Some(MerkleTree::<Sha256Hasher>::new(leaves).get_root()));
+
+    let err = Stream::new().push_fixed(&[1u8, 2, 3, 4, 5][..], 4).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn bad_delimited_input_is_an_error() {
+    let mut cut = delimited(&binary_leaves());
+    cut.pop();
+    assert_eq!(Stream::new().push_delimited(cut.as_slice()).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // a huge length prefix must fail before allocating
+    let huge = ((MAX_LEAF_LEN + 1) as u32).to_be_bytes();
+    assert_eq!(Stream::new().push_delimited(&huge[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+fn spill_dir(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("merkle_stream_{}_{}", name, std::process::id()))
+}
+
+
+// streamed and spilled trees give the same root and proofs as MerkleTree
+fn check_streaming<H: Hasher>(leaves: Vec<Vec<u8>>, name: &str) {
+    let tree = MerkleTree::<H>::new(leaves.clone());
+    let dir = spill_dir(name);
+
+    let mut stream = StreamingMerkleTree::<H>::with_spill(&dir).unwrap();
+    stream.extend(&leaves).unwrap();
+    assert_eq!(stream.finish().unwrap(), Some(tree.get_root()));
+
+    let spilled = SpilledMerkleTree::<H>::open(&dir).unwrap();
+    assert_eq!(spilled.len(), leaves.len());
+    assert_eq!(spilled.get_root().unwrap(), tree.get_root());
+    for i in 0..leaves.len() {
+        assert_eq!(spilled.get_proof(i).unwrap(), tree.get_proof(i));
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+// distinct leaves, equal leaves give equal subtrees and proofs that fit many paths
+fn leaf_set() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    prop::collection::btree_set(prop::collection::vec(any::<u8>(), 0..16), 1..24)
+        .prop_map(|set| set.into_iter().collect::<Vec<_>>())
+        .prop_shuffle()
+}
+
+proptest! {
+    #[test]
+    fn sha256_streaming(leaves in leaf_set()) {
+        check_streaming::<Sha256Hasher>(leaves, "sha256");
+    }
+
+    #[test]
+    fn sha512_streaming(leaves in leaf_set()) {
+        check_streaming::<Sha512Hasher>(leaves, "sha512");
+    }
+
+    #[test]
+    fn blake2b_streaming(leaves in leaf_set()) {
+        check_streaming::<Blake2bHasher>(leaves, "blake2b");
+    }
+}
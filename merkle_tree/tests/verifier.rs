@@ -0,0 +1,235 @@
+// the solidity verifiers run in an evm (revm) against the calldata the crate
+// builds. each contract is compiled here with solc 0.8.24, which has to be on
+// the path or in SOLC, e.g. `svm install 0.8.24`. the same as
+// `solc-0.8.24 --bin MerkleVerifier.sol` on the output of solidity_verifier().
+// the calldata and multiproof checks at the end run without solc
+use ethers::abi::{decode, encode, ParamType, Token};
+use merkle_tree::abi::{multi_verify_calldata, proof_calldata, solidity_verifier, verify_calldata};
+use merkle_tree::{Blake2bHasher, Hasher, Keccak256Hasher, MerkleTree, MultiProof, MultiProofError, Sha256Hasher, Sha512Hasher};
+use proptest::prelude::*;
+use revm::context::TxEnv;
+use revm::context_interface::result::{ExecutionResult, Output};
+use revm::database::{CacheDB, EmptyDB};
+use revm::primitives::{Address, Bytes, TxKind};
+use revm::{Context, ExecuteCommitEvm, MainBuilder, MainContext, MainnetEvm};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SOLC_VERSION: &str = "0.8.24";
+
+// creation code of the verifier for H
+fn compile<H: Hasher>() -> Vec<u8> {
+    let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+    let version = Command::new(&solc)
+        .arg("--version")
+        .output()
+        .unwrap_or_else(|e| panic!("can not run {}: {}, these tests need solc {}", solc, e, SOLC_VERSION));
+    let version = String::from_utf8_lossy(&version.stdout).into_owned();
+    assert!(version.contains(&format!("Version: {}+", SOLC_VERSION)), "need solc {}, {} is {}", SOLC_VERSION, solc, version);
+
+    let mut child = Command::new(&solc).args(["--bin", "-"]).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(solidity_verifier::<H>().unwrap().as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // the code is the line after "Binary:"
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let code = stdout.lines().skip_while(|line| !line.starts_with("Binary")).nth(1).expect("no Binary in the solc output");
+    hex::decode(code.trim()).unwrap()
+}
+
+type Evm = MainnetEvm<Context<revm::context::BlockEnv, TxEnv, revm::context::CfgEnv, CacheDB<EmptyDB>>>;
+
+struct Verifier {
+    evm: Evm,
+    address: Address,
+    nonce: u64,
+}
+
+impl Verifier {
+    fn deploy<H: Hasher>() -> Self {
+        let code = compile::<H>();
+        let mut evm = Context::mainnet().with_db(CacheDB::<EmptyDB>::default()).build_mainnet();
+        let tx = TxEnv::builder().kind(TxKind::Create).data(Bytes::from(code)).build().unwrap();
+        let address = match evm.transact_commit(tx).unwrap() {
+            ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+            result => panic!("deploy failed: {:?}", result),
+        };
+        Verifier { evm, address, nonce: 1 }
+    }
+
+    // abi decoded bool returned by the call
+    fn call(&mut self, calldata: &[u8]) -> bool {
+        let tx = TxEnv::builder().kind(TxKind::Call(self.address)).nonce(self.nonce).data(Bytes::from(calldata.to_vec())).build().unwrap();
+        self.nonce += 1;
+        match self.evm.transact_commit(tx).unwrap() {
+            ExecutionResult::Success { output: Output::Call(output), .. } => {
+                decode(&[ParamType::Bool], &output).unwrap()[0] == Token::Bool(true)
+            }
+            result => panic!("call failed: {:?}", result),
+        }
+    }
+}
+
+fn leaves(n: usize) -> Vec<Vec<u8>> {
+    (0..n).map(|i| format!("leaf{}", i).into_bytes()).collect()
+}
+
+// calldata with the last argument (the root) replaced
+fn with_root(calldata: &[u8], types: &[ParamType], root: &str) -> Vec<u8> {
+    let mut args = decode(types, &calldata[4..]).unwrap();
+    *args.last_mut().unwrap() = Token::FixedBytes(hex::decode(root).unwrap());
+    [&calldata[..4], &encode(&args)].concat()
+}
+
+fn verify_accepts_every_proof<H: Hasher>() {
+    let mut verifier = Verifier::deploy::<H>();
+    for n in 1..=17 {
+        let leaves = leaves(n);
+        let tree = MerkleTree::<H>::new(leaves.clone());
+        let root = tree.get_root();
+        let other = H::hash(b"other root");
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = tree.get_proof(i);
+            assert!(verifier.call(&verify_calldata::<H>(leaf, &proof, &root).unwrap()), "n {} leaf {}", n, i);
+            assert!(!verifier.call(&verify_calldata::<H>(leaf, &proof, &other).unwrap()), "n {} leaf {}", n, i);
+        }
+    }
+}
+
+fn multi_verify_accepts_every_multiproof<H: Hasher>() {
+    let types = [
+        ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+        ParamType::Array(Box::new(ParamType::Uint(256))),
+        ParamType::Uint(256),
+        ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+        ParamType::FixedBytes(32),
+    ];
+    let mut verifier = Verifier::deploy::<H>();
+    for n in 1..=8 {
+        let leaves = leaves(n);
+        let tree = MerkleTree::<H>::new(leaves.clone());
+        let root = tree.get_root();
+        // every non empty subset of the leaves
+        for mask in 1..(1u32 << n) {
+            let indices: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            let proof = tree.get_multiproof(&indices).unwrap();
+            let proven: Vec<Vec<u8>> = proof.indices.iter().map(|i| leaves[*i].clone()).collect();
+            let calldata = multi_verify_calldata::<H>(&proven, &proof, &root).unwrap();
+            assert!(verifier.call(&calldata), "n {} indices {:?}", n, indices);
+            let wrong = with_root(&calldata, &types, &H::hash(b"other root"));
+            assert!(!verifier.call(&wrong), "n {} indices {:?}", n, indices);
+        }
+    }
+}
+
+#[test]
+fn sha256_verify_accepts_every_proof() {
+    verify_accepts_every_proof::<Sha256Hasher>();
+}
+
+#[test]
+fn sha256_multi_verify_accepts_every_multiproof() {
+    multi_verify_accepts_every_multiproof::<Sha256Hasher>();
+}
+
+#[test]
+fn keccak256_verify_accepts_every_proof() {
+    verify_accepts_every_proof::<Keccak256Hasher>();
+}
+
+#[test]
+fn keccak256_multi_verify_accepts_every_multiproof() {
+    multi_verify_accepts_every_multiproof::<Keccak256Hasher>();
+}
+
+#[test]
+fn calldata_matches_proof() {
+    let leaves = leaves(7);
+    let tree = MerkleTree::<Sha256Hasher>::new(leaves.clone());
+    let root = tree.get_root();
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = tree.get_proof(i);
+        let encoded = proof_calldata::<Sha256Hasher>(leaf, &proof).unwrap();
+        let tokens = decode(&[ParamType::Array(Box::new(ParamType::FixedBytes(32)))], &encoded).unwrap();
+        let Token::Array(siblings) = &tokens[0] else { panic!("not an array") };
+        assert_eq!(siblings.len(), proof.len());
+
+        let call = verify_calldata::<Sha256Hasher>(leaf, &proof, &root).unwrap();
+        assert_eq!(&call[..4], &ethers::utils::id("verify(bytes32[],bytes32,bytes32)")[..]);
+        let args = decode(
+            &[ParamType::Array(Box::new(ParamType::FixedBytes(32))), ParamType::FixedBytes(32), ParamType::FixedBytes(32)],
+            &call[4..],
+        )
+        .unwrap();
+        assert_eq!(args[1], Token::FixedBytes(hex::decode(&root).unwrap()));
+        assert_eq!(args[2], Token::FixedBytes(hex::decode(Sha256Hasher::hash(leaf)).unwrap()));
+    }
+
+    // proof for other leaf give no calldata
+    assert!(proof_calldata::<Sha256Hasher>(b"other", &tree.get_proof(0)).is_none());
+
+    let multi = tree.get_multiproof(&[1, 2, 6]).unwrap();
+    let proven: Vec<Vec<u8>> = multi.indices.iter().map(|i| leaves[*i].clone()).collect();
+    assert!(multi_verify_calldata::<Sha256Hasher>(&proven, &multi, &root).is_some());
+    assert!(multi_verify_calldata::<Sha256Hasher>(&leaves[..3], &multi, &root).is_none());
+
+    // 64 byte hashes do not fit bytes32
+    let wide = MerkleTree::<Sha512Hasher>::new(leaves.clone());
+    assert!(proof_calldata::<Sha512Hasher>(&leaves[0], &wide.get_proof(0)).is_none());
+    assert!(solidity_verifier::<Sha256Hasher>().is_some());
+    assert!(solidity_verifier::<Keccak256Hasher>().is_some());
+    assert!(solidity_verifier::<Sha512Hasher>().is_none());
+    assert!(solidity_verifier::<Blake2bHasher>().is_none());
+}
+
+fn check_multiproof<H: Hasher>(leaves: Vec<Vec<u8>>, picks: Vec<usize>, drop: usize) {
+    let tree = MerkleTree::<H>::new(leaves.clone());
+    let root = tree.get_root();
+    let proof = tree.get_multiproof(&picks.iter().map(|p| p % leaves.len()).collect::<Vec<_>>()).unwrap();
+    let proven: Vec<Vec<u8>> = proof.indices.iter().map(|i| leaves[*i].clone()).collect();
+
+    assert!(MerkleTree::<H>::verify_multiproof(&proven, &proof, &root));
+
+    // every leaf proven at once needs no proof nodes
+    let all = tree.get_multiproof(&(0..leaves.len()).collect::<Vec<_>>()).unwrap();
+    assert_eq!(tree.get_multiproof(&[leaves.len()]), Err(MultiProofError::OutOfRange { index: leaves.len(), leaf_count: leaves.len() }));
+    assert_eq!(tree.get_multiproof(&[]), Err(MultiProofError::NoIndices));
+    assert!(all.proof.is_empty());
+    assert!(MerkleTree::<H>::verify_multiproof(&leaves, &all, &root));
+
+    if !proof.proof.is_empty() {
+        let mut dropped: MultiProof = proof.clone();
+        dropped.proof.remove(drop % proof.proof.len());
+        assert!(!MerkleTree::<H>::verify_multiproof(&proven, &dropped, &root));
+    }
+    let mut wrong = proven.clone();
+    wrong[0].push(0xff);
+    if !leaves.contains(&wrong[0]) {
+        assert!(!MerkleTree::<H>::verify_multiproof(&wrong, &proof, &root));
+    }
+}
+
+// distinct leaves, equal leaves give equal subtrees and proofs that fit many paths
+fn leaf_set() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    prop::collection::btree_set(prop::collection::vec(any::<u8>(), 0..16), 1..24)
+        .prop_map(|set| set.into_iter().collect::<Vec<_>>())
+        .prop_shuffle()
+}
+
+proptest! {
+    #[test]
+    fn sha256_multiproof(leaves in leaf_set(), picks in prop::collection::vec(any::<usize>(), 1..8), drop: usize) {
+        check_multiproof::<Sha256Hasher>(leaves, picks, drop);
+    }
+
+    #[test]
+    fn sha512_multiproof(leaves in leaf_set(), picks in prop::collection::vec(any::<usize>(), 1..8), drop: usize) {
+        check_multiproof::<Sha512Hasher>(leaves, picks, drop);
+    }
+
+    #[test]
+    fn blake2b_multiproof(leaves in leaf_set(), picks in prop::collection::vec(any::<usize>(), 1..8), drop: usize) {
+        check_multiproof::<Blake2bHasher>(leaves, picks, drop);
+    }
+}
@@ -1,29 +1,17 @@
-use ethers::types::TransactionRequest as EthersTxRequest;
-use ethers::types::transaction::eip2718::TypedTransaction;
+use eth_client::signer::private_key_hex;
+use eth_client::{env, random_wallet, Client, Error, Outcome, TxBuilder};
+use ethers::abi::Token;
+use ethers::signers::Signer;
 use web3::contract::{Contract, Options};
-use ethers::prelude::*;
-use ethers::signers::LocalWallet;
-use web3::transports::Http;
 use web3::types::U256;
-use web3::Web3;
-use dotenv::dotenv;
-use std::env;
-use hex::encode;
-use ethers::abi::Token;
 
 
 #[tokio::main]
-async fn main() -> web3::Result<()> {
-
-    // let mut i=0;
-    // while i<10
-    // {
-       
-    dotenv().ok(); // Load .env file
+async fn main() -> eth_client::Result<()> {
 
     // Generate a new Ethereum wallet
-    let wallet = LocalWallet::new(&mut rand::thread_rng());
-    let (wallet_address, private_key) = (wallet.address(), encode(wallet.signer().to_bytes()));
+    let wallet = random_wallet();
+    let (wallet_address, private_key) = (wallet.address(), private_key_hex(&wallet));
 
     //receiver address
     let recv_sddr= "0x40C34974068CBe7Ef930e4585a68740a7aee2B89"; // chnage to specific
@@ -32,60 +20,44 @@ async fn main() -> web3::Result<()> {
     println!("Private Key: {:?}", private_key);
 
 
-    // Connect to Ethereum node (Infura, Alchemy, or Local Node)
-    let web3 = Web3::new(Http::new(&env::var("ETHEREUM_RPC_URL").expect("Missing ETHEREUM_RPC_URL"))?);
-    
+    // Connect to Ethereum node (Infura, Alchemy, or Local Node), url from .env
+    let client = Client::from_env()?;
 
     // Fetch balance
-    let balance: U256 = web3.eth().balance(wallet_address, None).await?;
-    let balance_in_eth = ethers::utils::format_units(balance, "ether").unwrap();
-    println!("Balance New Wallet: {} ETH", balance_in_eth);
+    println!("Balance New Wallet: {} ETH", client.balance_eth(wallet_address).await?);
 
     // Send transaction (if balance is sufficient)
-    let sender_wallet: LocalWallet = env::var("SENDER_PRIVATE_KEY").expect("Missing SENDER_PRIVATE_KEY").parse().unwrap();
+    let sender_wallet = env::wallet(env::SENDER_PRIVATE_KEY)?;
     let sender = sender_wallet.address();
 
     //check balance of Sender
-    let balance_sender: U256 = web3.eth().balance(sender, None).await?;
-    let balance_in_eth_sender = ethers::utils::format_units(balance_sender, "ether").unwrap();
-    println!("Balance Of Sender: {} ETH", balance_in_eth_sender);
-
-    let (gas_price, gas_limit, value) = (web3.eth().gas_price().await?, U256::from(21000), U256::exp10(16));
-
-    if balance_sender < value + (gas_limit * gas_price) {
-        println!("Insufficient balance for transaction.");
-        return Ok(());
-    }
-
-    let nonce = web3.eth().transaction_count(sender, None).await?;
-    let chain_id = web3.eth().chain_id().await?.as_u64();
-
-
-    let mut tx: TypedTransaction = EthersTxRequest {
-        from: Some(sender),
-        // to: Some(ethers::types::NameOrAddress::Address(wallet_address)),
-        to:Some(recv_sddr.parse().unwrap()),// uncomment this line to send to specific address
-        value: Some(value),
-        gas: Some(gas_limit),
-        gas_price: Some(gas_price),
-        nonce: Some(nonce),
-        ..Default::default()
-    }
-    .into();
-
-    tx.set_chain_id(chain_id);
-    let signature = sender_wallet.sign_transaction(&tx).await.unwrap();
-    let bytes: Vec<u8> = tx.rlp_signed(&signature).to_vec();
-    let tx_hash = web3.eth().send_raw_transaction(web3::types::Bytes(bytes)).await.unwrap();
+    println!("Balance Of Sender: {} ETH", client.balance_eth(sender).await?);
+
+    // fee speed from TX_SPEED in .env (slow, standard, fast), type 2 fees where the chain has a base fee
+    let speed = env::speed()?;
+    println!("Fees ({}): {:?}", speed, client.fees(speed).await?);
+
+    // let transfer = TxBuilder::transfer(wallet_address, U256::exp10(16));
+    let transfer = TxBuilder::transfer(env::parse_address(recv_sddr)?, U256::exp10(16)).speed(speed); // to specific address
+    let tx_hash = match client.send(&sender_wallet, &transfer).await {
+        Err(Error::InsufficientBalance { .. }) => {
+            println!("Insufficient balance for transaction.");
+            return Ok(());
+        }
+        result => result?,
+    };
     println!("Transaction sent! Hash: {:?}", tx_hash);
 
     println!("Transaction Receipt Generating....");
 
-        while web3.eth().transaction_receipt(tx_hash).await?.is_none() {
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-        }
-
-        let receipt = web3.eth().transaction_receipt(tx_hash).await?.unwrap();
+        let outcome = client.wait_for_receipt(tx_hash).await?;
+        let receipt = match outcome.receipt() {
+            Some(receipt) => receipt,
+            None => {
+                println!("Transaction {}", outcome);
+                return Ok(());
+            }
+        };
         println!("=============================");
         println!("||==>Transaction Receipt:<===");
         println!("=============================");
@@ -96,55 +68,35 @@ async fn main() -> web3::Result<()> {
         println!("||  To: {:?}", receipt.to.unwrap_or_default());
         println!("||  Gas Used: {:?}", receipt.gas_used.unwrap_or_default());
         println!("||  Status: {:?}", if receipt.status == Some(1.into()) { "Success" } else { "Failed" });
+        if let Outcome::Reverted { reason: Some(reason), .. } = &outcome {
+            println!("||  Revert Reason: {}", reason);
+        }
         println!("=============================");
 
 
         //intreracting with contract
         println!("Interacting with the smart contract...");
 
-        let contract_address: Address = env::var("CONTRACT_ADDRESS").expect("CONTRACT_ADDRESS not found in .env").parse::<H160>().unwrap();
-        let contract = Contract::from_json(web3.eth(), contract_address, include_str!("storage_abi.json").as_bytes()).unwrap();
-        
+        let contract_address = env::address(env::CONTRACT_ADDRESS)?;
+        let contract = Contract::from_json(client.web3().eth(), contract_address, include_str!("storage_abi.json").as_bytes())?;
 
-        // Store a new value in the contract
-        let store_value: U256 = 100.into();
-        let nonce = web3.eth().transaction_count(sender, None).await?;
-
-
-        let mut tx: TypedTransaction = EthersTxRequest {
-            from: Some(sender),
-            to: Some(ethers::types::NameOrAddress::Address(contract_address)),
-            gas: Some(U256::from(100000)),
-            gas_price: Some(gas_price),
-            nonce: Some(nonce),
-            data: Some(contract.abi().function("store").unwrap().encode_input(&[Token::Uint(store_value)]).unwrap().into()),
-            value: Some(U256::zero()), // No ETH transfer, just function call
-            ..Default::default()
-        }
-        .into();
 
-
-        tx.set_chain_id(chain_id);
-        let signature = sender_wallet.sign_transaction(&tx).await.unwrap();
-        let bytes: Vec<u8> = tx.rlp_signed(&signature).to_vec();
-        let tx_hash = web3.eth().send_raw_transaction(web3::types::Bytes(bytes)).await.unwrap();
+        // Store a new value in the contract, no ETH transfer, just function call
+        let store_value: U256 = 100.into();
+        let data = contract.abi().function("store")?.encode_input(&[Token::Uint(store_value)])?;
+        let tx_hash = client.send(&sender_wallet, &TxBuilder::call(contract_address, data).speed(speed)).await?;
         println!("Stored Value Transaction Hash: {:?}", tx_hash);
 
 
         println!("Waiting for transaction Confirmation...");
-        while web3.eth().transaction_receipt(tx_hash).await?.is_none() {
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        match client.wait_for_receipt(tx_hash).await? {
+            Outcome::Mined(_) => println!("Transaction confirmed!"),
+            outcome => println!("Transaction {}", outcome),
         }
-        println!("Transaction confirmed!");
 
         // Retrieve stored value
-        let stored_value: U256 = contract.query("retrieve", (), None, Options::default(), None).await.unwrap();
+        let stored_value: U256 = contract.query("retrieve", (), None, Options::default(), None).await?;
         println!("Stored Value in Contract: {}", stored_value);
 
-    //     i+=1;
-    // }
-
     Ok(())
 }
-
-
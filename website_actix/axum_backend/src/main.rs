@@ -0,0 +1,80 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use eth_client::{env, parse_wallet, Client, Error, Speed, TxBuilder};
+use serde::{Deserialize, Serialize};
+use tower_http::cors::{Any, CorsLayer};
+use web3::transports::Http;
+
+// Structs for API requests
+#[derive(Deserialize)]
+struct TransactionRequest {
+    sender_private_key: String,
+    recipient: String,
+    amount: f64,
+    // slow, standard or fast, standard when left out
+    #[serde(default)]
+    speed: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TransactionResponse {
+    tx_hash: String,
+}
+
+#[tokio::main]
+async fn main() {
+    // one client for every request, it is cheap to clone
+    let client = Client::from_env().expect("Missing ETHEREUM_RPC_URL");
+
+    let app = Router::new()
+        .route("/", get(health_check))
+        .route("/send_transaction", post(send_transaction))
+        .layer(CorsLayer::new().allow_origin(Any)) // Allow frontend requests
+        .with_state(client);
+
+    println!("🚀 Server running on http://localhost:8000");
+    axum::Server::bind(&"0.0.0.0:8000".parse().unwrap())
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+async fn health_check() -> &'static str {
+    "Backend is running"
+}
+
+// bad input and a sender that can not pay are the caller's fault, the rest is ours
+fn error_response(e: Error) -> (StatusCode, String) {
+    match e {
+        Error::InvalidKey(_) | Error::InvalidAddress(_) | Error::InvalidSpeed(_) | Error::InsufficientBalance { .. } => (StatusCode::BAD_REQUEST, e.to_string()),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn send_transaction(
+    State(client): State<Client<Http>>,
+    Json(payload): Json<TransactionRequest>,
+) -> Result<Json<TransactionResponse>, (StatusCode, String)> {
+    let sender_wallet = parse_wallet(&payload.sender_private_key).map_err(error_response)?;
+    let recipient = env::parse_address(&payload.recipient).map_err(error_response)?;
+    let value = ethers::utils::parse_units(payload.amount, "ether")
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let speed = match payload.speed.as_deref() {
+        Some(speed) => speed.parse().map_err(error_response)?,
+        None => Speed::default(),
+    };
+
+    let tx_hash = client
+        .send(&sender_wallet, &TxBuilder::transfer(recipient, value.into()).speed(speed))
+        .await
+        .map_err(error_response)?;
+
+    Ok(Json(TransactionResponse {
+        tx_hash: format!("{:?}", tx_hash),
+    }))
+}
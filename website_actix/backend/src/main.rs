@@ -1,15 +1,11 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use actix_cors::Cors;
-use ethers::prelude::*;
-use ethers::types::transaction::eip2718::TypedTransaction;
-use ethers::types::TransactionRequest as EthersTxRequest;
+use eth_client::signer::private_key_hex;
+use eth_client::{env, parse_wallet, random_wallet, Client, Error, Outcome, Speed, TxBuilder};
+use ethers::signers::{LocalWallet, Signer};
 use web3::transports::Http;
-use web3::types::U256;
-use web3::Web3;
-use dotenv::dotenv;
-use hex::encode;
+use web3::types::{Address, H256, U256};
 use serde::{Deserialize, Serialize};
-use std::env;
 
 #[derive(Serialize)]
 struct WalletResponse {
@@ -28,6 +24,8 @@ struct BalanceResponse {
 struct TransactionResponse {
     tx_hash: String,
     receipt: Option<TransactionReceipt>,
+    // mined, reverted with the reason, replaced, dropped or timed out
+    outcome: String,
 }
 
 #[derive(Serialize)]
@@ -50,16 +48,30 @@ struct BalanceQuery {
 struct TransactionRequest {
     sender_private_key: String,
     receiver_address: String,
+    // slow, standard or fast, standard when left out
+    #[serde(default)]
+    speed: Option<String>,
 }
 
-async fn generate_wallet() -> impl Responder {
-    dotenv().ok();
-    let wallet = LocalWallet::new(&mut rand::thread_rng());
+// bad input and a sender that can not pay are the caller's fault, the rest is ours
+fn error_response(e: Error) -> HttpResponse {
+    match e {
+        Error::InvalidKey(_) => HttpResponse::BadRequest().body("Invalid sender private key"),
+        Error::InvalidAddress(_) => HttpResponse::BadRequest().body("Invalid Ethereum address"),
+        Error::InsufficientBalance { .. } => HttpResponse::BadRequest().body("Insufficient balance for transaction."),
+        Error::InvalidSpeed(_) => HttpResponse::BadRequest().body(e.to_string()),
+        e => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn generate_wallet(client: web::Data<Client<Http>>) -> impl Responder {
+    let wallet = random_wallet();
     let wallet_address = format!("{:?}", wallet.address());
-    let private_key = encode(wallet.signer().to_bytes());
-    let web3 = Web3::new(Http::new(&env::var("ETHEREUM_RPC_URL").expect("Missing ETHEREUM_RPC_URL")).unwrap());
-    let balance: U256 = web3.eth().balance(wallet.address(), None).await.unwrap();
-    let balance_in_eth = ethers::utils::format_units(balance, "ether").unwrap();
+    let private_key = private_key_hex(&wallet);
+    let balance_in_eth = match balance_of(&client, wallet.address()).await {
+        Ok(balance) => balance,
+        Err(e) => return error_response(e),
+    };
 
     HttpResponse::Ok().json(WalletResponse {
         address: wallet_address,
@@ -68,18 +80,15 @@ async fn generate_wallet() -> impl Responder {
     })
 }
 
-async fn get_balance(query: web::Query<BalanceQuery>) -> impl Responder {
-    dotenv().ok();
-    let web3 = Web3::new(Http::new(&env::var("ETHEREUM_RPC_URL").expect("Missing ETHEREUM_RPC_URL")).unwrap());
-    let address: Address = match query.address.parse() {
+async fn get_balance(client: web::Data<Client<Http>>, query: web::Query<BalanceQuery>) -> impl Responder {
+    let address = match env::parse_address(&query.address) {
         Ok(addr) => addr,
         Err(_) => return HttpResponse::BadRequest().body("Invalid Ethereum address"),
     };
-    let balance: U256 = match web3.eth().balance(address, None).await {
-        Ok(b) => b,
+    let balance_in_eth = match balance_of(&client, address).await {
+        Ok(balance) => balance,
         Err(_) => return HttpResponse::InternalServerError().body("Failed to fetch balance"),
     };
-    let balance_in_eth = ethers::utils::format_units(balance, "ether").unwrap();
 
     HttpResponse::Ok().json(BalanceResponse {
         address: query.address.clone(),
@@ -87,74 +96,63 @@ async fn get_balance(query: web::Query<BalanceQuery>) -> impl Responder {
     })
 }
 
-async fn send_transaction(data: web::Json<TransactionRequest>) -> impl Responder {
+async fn send_transaction(client: web::Data<Client<Http>>, data: web::Json<TransactionRequest>) -> impl Responder {
     println!("Received POST /send-transaction");
-    dotenv().ok();
-    let web3 = Web3::new(Http::new(&env::var("ETHEREUM_RPC_URL").expect("Missing ETHEREUM_RPC_URL")).unwrap());
-    let sender_wallet: LocalWallet = match data.sender_private_key.parse() {
+    let sender_wallet = match parse_wallet(&data.sender_private_key) {
         Ok(wallet) => wallet,
-
         Err(_) => return HttpResponse::BadRequest().body("Invalid sender private key"),
     };
-    let sender = sender_wallet.address();
-    let recv_addr: Address = match data.receiver_address.parse() {
+    let recv_addr = match env::parse_address(&data.receiver_address) {
         Ok(addr) => addr,
         Err(_) => return HttpResponse::BadRequest().body("Invalid receiver address"),
     };
+    let speed = match data.speed.as_deref().map(str::parse::<Speed>) {
+        Some(Ok(speed)) => speed,
+        Some(Err(e)) => return error_response(e),
+        None => Speed::default(),
+    };
 
-    let balance_sender: U256 = web3.eth().balance(sender, None).await.unwrap();
-    let (gas_price, gas_limit, value) = (web3.eth().gas_price().await.unwrap(), U256::from(21000), U256::exp10(16));
-
-    if balance_sender < value + (gas_limit * gas_price) {
-        return HttpResponse::BadRequest().body("Insufficient balance for transaction.");
-    }
-
-    let nonce = web3.eth().transaction_count(sender, None).await.unwrap();
-    let chain_id = web3.eth().chain_id().await.unwrap().as_u64();
-
-    let mut tx: TypedTransaction = EthersTxRequest {
-        from: Some(sender),
-        to: Some(recv_addr.into()),
-        value: Some(value),
-        gas: Some(gas_limit),
-        gas_price: Some(gas_price),
-        nonce: Some(nonce),
-        ..Default::default()
-    }
-    .into();
-
-    tx.set_chain_id(chain_id);
-    let signature = sender_wallet.sign_transaction(&tx).await.unwrap();
-    let bytes: Vec<u8> = tx.rlp_signed(&signature).to_vec();
-    let tx_hash = web3.eth().send_raw_transaction(web3::types::Bytes(bytes)).await.unwrap();
-
-    while web3.eth().transaction_receipt(tx_hash).await.unwrap().is_none() {
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    match transfer(&client, &sender_wallet, recv_addr, speed).await {
+        Ok((tx_hash, outcome)) => {
+            let receipt_data = outcome.receipt().map(|receipt| TransactionReceipt {
+                transaction_index: receipt.transaction_index.as_u64(),
+                transaction_hash: format!("{:?}", receipt.transaction_hash),
+                block_number: receipt.block_number.unwrap_or_default().as_u64(),
+                from: format!("{:?}", receipt.from),
+                to: format!("{:?}", receipt.to.unwrap_or_default()),
+                gas_used: format!("{:?}", receipt.gas_used.unwrap_or_default()),
+                status: if receipt.status == Some(1.into()) { "Success" } else { "Failed" }.to_string(),
+            });
+
+            HttpResponse::Ok().json(TransactionResponse {
+                tx_hash: format!("{:?}", tx_hash),
+                receipt: receipt_data,
+                outcome: outcome.to_string(),
+            })
+        }
+        Err(e) => error_response(e),
     }
+}
 
-    let receipt = web3.eth().transaction_receipt(tx_hash).await.unwrap().unwrap();
-
-    let receipt_data = TransactionReceipt {
-        transaction_index: receipt.transaction_index.as_u64(),
-        transaction_hash: format!("{:?}", receipt.transaction_hash),
-        block_number: receipt.block_number.unwrap_or_default().as_u64(),
-        from: format!("{:?}", receipt.from),
-        to: format!("{:?}", receipt.to.unwrap_or_default()),
-        gas_used: format!("{:?}", receipt.gas_used.unwrap_or_default()),
-        status: if receipt.status == Some(1.into()) { "Success" } else { "Failed" }.to_string(),
-    };
+async fn balance_of(client: &Client<Http>, address: Address) -> eth_client::Result<String> {
+    client.balance_eth(address).await
+}
 
-    HttpResponse::Ok().json(TransactionResponse {
-        tx_hash: format!("{:?}", tx_hash),
-        receipt: Some(receipt_data),
-    })
+// sends 0.01 ETH and waits for it to be mined, dropped or the timeout
+async fn transfer(client: &Client<Http>, sender_wallet: &LocalWallet, recv_addr: Address, speed: Speed) -> eth_client::Result<(H256, Outcome)> {
+    let tx_hash = client.send(sender_wallet, &TxBuilder::transfer(recv_addr, U256::exp10(16)).speed(speed)).await?;
+    Ok((tx_hash, client.wait_for_receipt(tx_hash).await?))
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    // one client for every request, it is cheap to clone
+    let client = web::Data::new(Client::from_env().expect("Missing ETHEREUM_RPC_URL"));
+
     println!("Server running on http://127.0.0.1:8080");
-    HttpServer::new(|| {
+    HttpServer::new(move || {
       let app = App::new()
+            .app_data(client.clone())
             .wrap(
                 Cors::default()
                     .allowed_origin("http://localhost:5173")
@@ -56,6 +56,10 @@ function App() {
         receiver_address: receiverAddress,
       });
       setTransaction(response.data);
+      if (!response.data.receipt) {
+        toast.warn('Transaction ' + response.data.outcome); // replaced, dropped or timed out
+        return;
+      }
       setShowPopup(true);
     } catch (error) {
       console.error('Error sending transaction:', error);